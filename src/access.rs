@@ -0,0 +1,272 @@
+//! Access control — who may connect, which request paths are never served
+//! from disk, and who may open the `/__lr` control channel.
+//!
+//! Clients are checked first ([`AccessPolicy`]): `--loopback-only` admits only
+//! this machine, `--allow-ip` adds CIDRs next to loopback, and `--basic-auth`
+//! asks everyone for `user:pass`. Denied requests are logged as `access_denied`.
+//!
+//! A client that passed the Basic challenge gets a session cookie, so later
+//! requests don't need the dev credentials in `Authorization` — the app behind
+//! the proxy can send its own `Bearer` token there. The dev credentials and the
+//! cookie are stripped before a request reaches the file server or a backend.
+//!
+//! The root is often the workspace itself, and the default host is 0.0.0.0,
+//! so without this `.hotplate/certs/hotplate.key`, `.hotplate/logs/` and `.git/`
+//! would be readable from the LAN. Denied paths get a plain 404, as if absent:
+//!   - `.hotplate/` — always
+//!   - dotfiles and dot-directories (`.git/`, `.env`) — unless `--allow-dotfiles`
+//!     (`.well-known/` is always allowed)
+//!   - sensitive globs — [`DEFAULT_DENY`] plus `--deny` patterns
+//!
+//! `/__lr` carries MCP responses (screenshots, DOM, eval results) and browser
+//! logs, so the handshake needs the per-session token embedded in the injected
+//! script, and browsers must connect from the page's own origin.
+
+use anyhow::{Context, Result};
+use axum::http::{header, HeaderMap, HeaderValue};
+use base64::Engine;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ipnet::IpNet;
+use percent_encoding::percent_decode_str;
+use std::net::IpAddr;
+
+// ───────────────────── Client access ─────────────────────
+
+/// Parse an `--allow-ip` entry: a CIDR (`192.168.1.0/24`) or a single address.
+pub fn parse_allowed_ip(entry: &str) -> Result<IpNet> {
+    let entry = entry.trim();
+    entry
+        .parse::<IpNet>()
+        .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+        .with_context(|| format!("Invalid --allow-ip '{}' (expected a CIDR like 192.168.1.0/24 or an IP)", entry))
+}
+
+/// Why a client was turned away (the `reason` of `access_denied` events).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Denial {
+    /// Not loopback, and `--loopback-only` is on.
+    LoopbackOnly,
+    /// Not loopback and not in `--allow-ip`.
+    IpNotAllowed,
+    /// Neither a session cookie nor an `Authorization` header.
+    AuthRequired,
+    /// Wrong user or password.
+    BadCredentials,
+}
+
+impl Denial {
+    pub fn reason(self) -> &'static str {
+        match self {
+            Denial::LoopbackOnly => "loopback_only",
+            Denial::IpNotAllowed => "ip_not_allowed",
+            Denial::AuthRequired => "auth_required",
+            Denial::BadCredentials => "bad_credentials",
+        }
+    }
+}
+
+/// Cookie that stands in for the Basic credentials after the first challenge.
+const SESSION_COOKIE: &str = "hotplate_session";
+
+/// How an admitted client got in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granted {
+    /// No credentials needed (or `/__lr`).
+    Open,
+    /// Valid session cookie.
+    Session,
+    /// Valid Basic credentials — the response should set the session cookie.
+    Credentials,
+}
+
+/// Who may talk to the server. Loopback always passes the IP checks.
+pub struct AccessPolicy {
+    loopback_only: bool,
+    allow: Vec<IpNet>,
+    /// Expected `user:pass`.
+    basic_auth: Option<String>,
+    /// Session cookie value, new for every policy (so a config reload that
+    /// changes the credentials also ends existing sessions).
+    session: String,
+}
+
+impl AccessPolicy {
+    /// `None` when nothing is restricted. `allow` entries were validated by `build_config`.
+    pub fn new(loopback_only: bool, allow: &[String], basic_auth: Option<String>) -> Option<Self> {
+        let allow: Vec<IpNet> = allow.iter().filter_map(|a| parse_allowed_ip(a).ok()).collect();
+        (loopback_only || !allow.is_empty() || basic_auth.is_some()).then(|| Self {
+            loopback_only,
+            allow,
+            basic_auth,
+            session: new_token(),
+        })
+    }
+
+    /// Check the peer address, then the session cookie or the `Authorization`
+    /// header (skipped for `/__lr`, which has its own token).
+    pub fn check(&self, peer: Option<IpAddr>, headers: &HeaderMap, path: &str) -> Result<Granted, Denial> {
+        if let Some(ip) = peer.map(|ip| ip.to_canonical()) {
+            if !ip.is_loopback() {
+                if self.loopback_only {
+                    return Err(Denial::LoopbackOnly);
+                }
+                if !self.allow.is_empty() && !self.allow.iter().any(|net| net.contains(&ip)) {
+                    return Err(Denial::IpNotAllowed);
+                }
+            }
+        }
+
+        let Some(ref expected) = self.basic_auth else { return Ok(Granted::Open) };
+        if path == "/__lr" {
+            return Ok(Granted::Open);
+        }
+        if cookies(headers).any(|(name, value)| name == SESSION_COOKIE && token_matches(value, &self.session)) {
+            return Ok(Granted::Session);
+        }
+        let Some(header) = headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok()) else {
+            return Err(Denial::AuthRequired);
+        };
+        match basic_credentials(header) {
+            Some(given) if token_matches(&given, expected) => Ok(Granted::Credentials),
+            _ => Err(Denial::BadCredentials),
+        }
+    }
+
+    /// `Set-Cookie` value for a client that just passed the Basic challenge.
+    pub fn session_cookie(&self) -> String {
+        format!("{}={}; Path=/; HttpOnly; SameSite=Lax", SESSION_COOKIE, self.session)
+    }
+
+    /// Remove the dev credentials (Basic `Authorization`, session cookie) so
+    /// they never reach a proxy target. An app's own `Authorization` stays.
+    pub fn strip_credentials(&self, headers: &mut HeaderMap) {
+        let Some(ref expected) = self.basic_auth else { return };
+        let is_dev_auth = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(basic_credentials)
+            .is_some_and(|given| token_matches(&given, expected));
+        if is_dev_auth {
+            headers.remove(header::AUTHORIZATION);
+        }
+
+        if !cookies(headers).any(|(name, _)| name == SESSION_COOKIE) {
+            return;
+        }
+        let rest: Vec<String> = cookies(headers)
+            .filter(|(name, _)| *name != SESSION_COOKIE)
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        headers.remove(header::COOKIE);
+        if rest.is_empty() {
+            return;
+        }
+        if let Ok(value) = HeaderValue::from_str(&rest.join("; ")) {
+            headers.insert(header::COOKIE, value);
+        }
+    }
+}
+
+/// `user:pass` from a `Basic` authorization header.
+fn basic_credentials(header: &str) -> Option<String> {
+    let b64 = header.strip_prefix("Basic ")?;
+    let bytes = base64::engine::general_purpose::STANDARD.decode(b64.trim()).ok()?;
+    String::from_utf8(bytes).ok()
+}
+
+/// `(name, value)` pairs from all `Cookie` headers.
+fn cookies(headers: &HeaderMap) -> impl Iterator<Item = (&str, &str)> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+}
+
+// ───────────────────── Protected paths ─────────────────────
+
+/// Private keys and keystores, denied even outside dot-directories.
+pub const DEFAULT_DENY: &[&str] = &[
+    "**/*.key",
+    "**/*.p12",
+    "**/*.pfx",
+    "**/id_rsa*",
+    "**/id_ecdsa*",
+    "**/id_ed25519*",
+];
+
+pub struct PathFilter {
+    allow_dotfiles: bool,
+    globs: GlobSet,
+}
+
+impl PathFilter {
+    /// `patterns` extend [`DEFAULT_DENY`]; invalid ones are reported and skipped.
+    pub fn new(patterns: &[String], allow_dotfiles: bool) -> Self {
+        let mut builder = GlobSetBuilder::new();
+        let all = DEFAULT_DENY.iter().copied().chain(patterns.iter().map(String::as_str));
+        for pattern in all {
+            // Case-insensitive: macOS and Windows serve `ID_RSA` for `id_rsa`
+            match GlobBuilder::new(pattern).case_insensitive(true).build() {
+                Ok(g) => {
+                    builder.add(g);
+                }
+                Err(e) => eprintln!("  ⚠ Invalid deny pattern '{}': {}", pattern, e),
+            }
+        }
+        Self {
+            allow_dotfiles,
+            globs: builder.build().unwrap_or_else(|_| GlobSet::empty()),
+        }
+    }
+
+    /// Whether the URL path (still percent-encoded) must not be served.
+    pub fn is_denied(&self, path: &str) -> bool {
+        // Decode like ServeDir does, so `/%2Ehotplate/` is caught too
+        let decoded = percent_decode_str(path).decode_utf8_lossy();
+        let rel = decoded.trim_start_matches('/');
+
+        for segment in rel.split(['/', '\\']) {
+            if segment.eq_ignore_ascii_case(".hotplate") {
+                return true;
+            }
+            if !self.allow_dotfiles
+                && segment.starts_with('.')
+                && segment != "."
+                && segment != ".well-known"
+            {
+                return true;
+            }
+        }
+        self.globs.is_match(rel)
+    }
+}
+
+// ───────────────────── /__lr handshake ─────────────────────
+
+/// Random per-session token for the `/__lr` handshake (32 hex chars).
+pub fn new_token() -> String {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).expect("OS random number generator unavailable");
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Compare tokens in constant time.
+pub fn token_matches(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// Whether a WebSocket `Origin` (`https://localhost:5500`) is the page's own
+/// origin, i.e. has the same host and port as the request's `host`.
+pub fn is_same_origin(origin: &str, host: &str) -> bool {
+    match origin.split_once("://") {
+        Some((_, authority)) => authority.trim_end_matches('/').eq_ignore_ascii_case(host),
+        None => false, // "null" (file://, sandboxed iframes)
+    }
+}
@@ -0,0 +1,259 @@
+//! Proxy capture — `--proxy-capture`.
+//!
+//! Records each proxied exchange as a `proxy_capture` event: method, path,
+//! upstream URL, status, timing, both header sets and the first
+//! [`BODY_LIMIT`] bytes of both bodies. Bodies are copied as they stream
+//! through ([`Tap`]), so uploads, downloads and SSE are never buffered; the
+//! event is written once the response body ends (or the client goes away).
+//!
+//! Credentials are redacted before anything is logged — the value, not the
+//! header, so agents still see that it was sent:
+//!   - `Authorization`, `Proxy-Authorization` → `Bearer [redacted]`
+//!   - `Cookie` → `sid=[redacted]; theme=[redacted]`
+//!   - `Set-Cookie` → `sid=[redacted]; Path=/; HttpOnly`
+//!   - headers named by `--proxy-capture-redact` → `[redacted]`
+
+use crate::events::{EventData, EventLogger};
+use axum::body::Bytes;
+use axum::http::HeaderMap;
+use base64::Engine;
+use futures_core::Stream;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+/// Bytes kept per body.
+pub const BODY_LIMIT: usize = 16 * 1024;
+
+const REDACTED: &str = "[redacted]";
+
+/// Turns header maps into loggable, redacted `name → value` maps.
+pub struct Redactor {
+    /// Extra header names (lower-case) whose values are hidden entirely.
+    extra: Vec<String>,
+}
+
+impl Redactor {
+    pub fn new(extra: &[String]) -> Self {
+        Self {
+            extra: extra.iter().map(|h| h.trim().to_ascii_lowercase()).collect(),
+        }
+    }
+
+    /// Repeated headers are joined with `, ` (`Set-Cookie` with newlines).
+    pub fn headers(&self, headers: &HeaderMap) -> BTreeMap<String, String> {
+        let mut out: BTreeMap<String, String> = BTreeMap::new();
+        for (name, value) in headers {
+            let name = name.as_str();
+            let value = self.redact(name, &String::from_utf8_lossy(value.as_bytes()));
+            out.entry(name.to_string())
+                .and_modify(|v| {
+                    v.push_str(if name == "set-cookie" { "\n" } else { ", " });
+                    v.push_str(&value);
+                })
+                .or_insert(value);
+        }
+        out
+    }
+
+    fn redact(&self, name: &str, value: &str) -> String {
+        match name {
+            "authorization" | "proxy-authorization" => match value.split_once(' ') {
+                Some((scheme, _)) => format!("{} {}", scheme, REDACTED),
+                None => REDACTED.to_string(),
+            },
+            "cookie" => value
+                .split(';')
+                .map(|pair| match pair.trim().split_once('=') {
+                    Some((cookie, _)) => format!("{}={}", cookie, REDACTED),
+                    None => REDACTED.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("; "),
+            "set-cookie" => {
+                let (pair, attrs) = value.split_once(';').unwrap_or((value, ""));
+                let cookie = pair.split_once('=').map_or(pair, |(cookie, _)| cookie).trim();
+                match attrs {
+                    "" => format!("{}={}", cookie, REDACTED),
+                    _ => format!("{}={};{}", cookie, REDACTED, attrs),
+                }
+            }
+            _ if self.extra.iter().any(|h| h == name) => REDACTED.to_string(),
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// The start of a body: UTF-8 text when it decodes, base64 otherwise.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CapturedBody {
+    /// Total bytes seen, including the part that wasn't kept.
+    pub size: u64,
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base64: Option<String>,
+    #[serde(skip)]
+    buf: Vec<u8>,
+}
+
+impl CapturedBody {
+    fn push(&mut self, chunk: &[u8]) {
+        self.size += chunk.len() as u64;
+        let room = BODY_LIMIT - self.buf.len();
+        if chunk.len() > room {
+            self.truncated = true;
+        }
+        self.buf.extend_from_slice(&chunk[..chunk.len().min(room)]);
+    }
+
+    fn finish(&mut self) {
+        let buf = std::mem::take(&mut self.buf);
+        if buf.is_empty() {
+            return;
+        }
+        match std::str::from_utf8(&buf) {
+            Ok(text) => self.text = Some(text.to_string()),
+            // Cut in the middle of a character by the limit: still text
+            Err(e) if self.truncated && e.error_len().is_none() => {
+                self.text = Some(String::from_utf8_lossy(&buf[..e.valid_up_to()]).into_owned());
+            }
+            Err(_) => self.text = None,
+        }
+        if self.text.is_none() {
+            self.base64 = Some(base64::engine::general_purpose::STANDARD.encode(&buf));
+        }
+    }
+}
+
+/// One exchange being captured, shared by the handler and the body taps.
+pub struct Exchange {
+    started: Instant,
+    method: String,
+    path: String,
+    target: String,
+    status: u16,
+    request_headers: BTreeMap<String, String>,
+    request_body: CapturedBody,
+    response_headers: BTreeMap<String, String>,
+    response_body: CapturedBody,
+    error: Option<String>,
+    logged: bool,
+}
+
+pub type SharedExchange = Arc<Mutex<Exchange>>;
+
+impl Exchange {
+    /// `headers` are the ones sent upstream.
+    pub fn start(redactor: &Redactor, method: &str, path: &str, target: &str, headers: &HeaderMap) -> SharedExchange {
+        Arc::new(Mutex::new(Self {
+            started: Instant::now(),
+            method: method.to_string(),
+            path: path.to_string(),
+            target: target.to_string(),
+            status: 0,
+            request_headers: redactor.headers(headers),
+            request_body: CapturedBody::default(),
+            response_headers: BTreeMap::new(),
+            response_body: CapturedBody::default(),
+            error: None,
+            logged: false,
+        }))
+    }
+
+    pub fn set_response(exchange: &SharedExchange, redactor: &Redactor, status: u16, headers: &HeaderMap) {
+        let mut ex = lock(exchange);
+        ex.status = status;
+        ex.response_headers = redactor.headers(headers);
+    }
+
+    /// Record a failed exchange (upstream unreachable, timeout) and log it.
+    pub fn fail(exchange: &SharedExchange, logger: &EventLogger, status: u16, error: String) {
+        {
+            let mut ex = lock(exchange);
+            ex.status = status;
+            ex.error = Some(error);
+        }
+        Self::finish(exchange, logger);
+    }
+
+    /// Log the exchange (once).
+    pub fn finish(exchange: &SharedExchange, logger: &EventLogger) {
+        let mut ex = lock(exchange);
+        if ex.logged {
+            return;
+        }
+        ex.logged = true;
+        ex.request_body.finish();
+        ex.response_body.finish();
+        logger.log(EventData::ProxyCapture {
+            method: ex.method.clone(),
+            path: ex.path.clone(),
+            target: ex.target.clone(),
+            status: ex.status,
+            duration_ms: ex.started.elapsed().as_millis() as u64,
+            request_headers: std::mem::take(&mut ex.request_headers),
+            request_body: Box::new(std::mem::take(&mut ex.request_body)),
+            response_headers: std::mem::take(&mut ex.response_headers),
+            response_body: Box::new(std::mem::take(&mut ex.response_body)),
+            error: ex.error.take(),
+        });
+    }
+}
+
+fn lock(exchange: &SharedExchange) -> std::sync::MutexGuard<'_, Exchange> {
+    exchange.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Which body a [`Tap`] copies.
+#[derive(Clone, Copy)]
+pub enum Side {
+    Request,
+    /// The response; dropping the tap (body done or client gone) logs the exchange.
+    Response,
+}
+
+/// Passes a body stream through unchanged, copying the start of it into the exchange.
+pub struct Tap<S> {
+    inner: S,
+    exchange: SharedExchange,
+    side: Side,
+    logger: EventLogger,
+}
+
+impl<S> Tap<S> {
+    pub fn new(inner: S, exchange: SharedExchange, side: Side, logger: EventLogger) -> Self {
+        Self { inner, exchange, side, logger }
+    }
+}
+
+impl<S, E> Stream for Tap<S>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+{
+    type Item = Result<Bytes, E>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = Pin::new(&mut self.inner).poll_next(cx);
+        if let Poll::Ready(Some(Ok(ref chunk))) = poll {
+            let mut ex = lock(&self.exchange);
+            match self.side {
+                Side::Request => ex.request_body.push(chunk),
+                Side::Response => ex.response_body.push(chunk),
+            }
+        }
+        poll
+    }
+}
+
+impl<S> Drop for Tap<S> {
+    fn drop(&mut self) {
+        if let Side::Response = self.side {
+            Exchange::finish(&self.exchange, &self.logger);
+        }
+    }
+}
@@ -0,0 +1,632 @@
+//! Dev certificates — a local root CA plus a leaf cert signed by it.
+//!
+//! The CA lives in `.hotplate/certs/ca/` and is created once (valid 10 years).
+//! Trust it on each device (`hotplate cert export-ca`) and every leaf it signs —
+//! `.hotplate/certs/hotplate.{crt,key}`, valid 90 days — is accepted without warnings,
+//! even after the leaf is regenerated.
+//!
+//! Used by `--https` and the `hotplate cert` subcommand:
+//!   hotplate cert create       # generate (or reuse) the cert
+//!   hotplate cert show         # print paths, subject, SANs and validity
+//!   hotplate cert regenerate   # delete and generate a fresh leaf cert (the CA is kept)
+//!   hotplate cert export-ca    # write the CA as PEM + DER for installing on devices
+
+use anyhow::{Context, Result};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Validity of the local CA.
+const CA_VALIDITY_DAYS: i64 = 3650;
+/// Validity of leaf certs — well under the 398-day limit browsers enforce.
+const LEAF_VALIDITY_DAYS: i64 = 90;
+/// Leaf certs are renewed this long before they expire.
+const RENEW_BEFORE_DAYS: i64 = 7;
+
+/// Paths of the auto-generated leaf cert and key: `<workspace>/.hotplate/certs/hotplate.{crt,key}`.
+pub(crate) fn dev_cert_paths(workspace: &Path) -> (PathBuf, PathBuf) {
+    let cert_dir = workspace.join(".hotplate").join("certs");
+    (cert_dir.join("hotplate.crt"), cert_dir.join("hotplate.key"))
+}
+
+/// File next to the dev cert recording the `cert_hosts` it was generated for.
+fn dev_cert_hosts_path(workspace: &Path) -> PathBuf {
+    workspace.join(".hotplate").join("certs").join("hotplate.hosts")
+}
+
+/// Paths of the local CA: `<workspace>/.hotplate/certs/ca/ca.{crt,key}`.
+pub(crate) fn ca_paths(workspace: &Path) -> (PathBuf, PathBuf) {
+    let ca_dir = workspace.join(".hotplate").join("certs").join("ca");
+    (ca_dir.join("ca.crt"), ca_dir.join("ca.key"))
+}
+
+/// Generate a leaf TLS certificate and key in `<workspace>/.hotplate/certs/`,
+/// signed by the local CA (created on first use).
+/// Returns the paths to the cert and key files.
+/// Existing files are reused unless they are stale: not issued by the current CA,
+/// expiring within [`RENEW_BEFORE_DAYS`], missing one of the wanted SANs, or
+/// generated for a different `cert_hosts` list.
+/// Also migrates old `.cert/` directory layout if found.
+pub(crate) fn generate_dev_cert(workspace: &Path, hosts: &[String]) -> Result<(PathBuf, PathBuf)> {
+    let (cert_path, key_path) = dev_cert_paths(workspace);
+    let cert_dir = workspace.join(".hotplate").join("certs");
+
+    // Migrate from old .cert/ layout → .hotplate/certs/
+    let old_cert_dir = workspace.join(".cert");
+    let old_cert = old_cert_dir.join("hotplate.crt");
+    let old_key = old_cert_dir.join("hotplate.key");
+    if old_cert.exists() && old_key.exists() && !cert_path.exists() {
+        std::fs::create_dir_all(&cert_dir)
+            .with_context(|| format!("Failed to create directory: {}", cert_dir.display()))?;
+        std::fs::rename(&old_cert, &cert_path).ok();
+        std::fs::rename(&old_key, &key_path).ok();
+        // Remove old directory if empty
+        let _ = std::fs::remove_dir(&old_cert_dir);
+        println!("  🔄 Migrated certs from .cert/ → .hotplate/certs/");
+    }
+
+    let ca = load_or_create_ca(workspace)?;
+
+    let sans = dev_cert_sans(hosts);
+    let hosts_path = dev_cert_hosts_path(workspace);
+    let wanted_hosts = normalize_cert_hosts(hosts);
+
+    // Reuse existing certs unless they are stale (wrong issuer, expiring, SANs changed)
+    if cert_path.exists() && key_path.exists() {
+        let reason = match read_cert_info(&cert_path) {
+            Ok(info) => stale_reason(&info, &ca.subject, &sans)
+                .or_else(|| hosts_changed(&read_cert_hosts(&hosts_path), &wanted_hosts)),
+            Err(e) => Some(format!("{:#}", e)),
+        };
+        match reason {
+            None => {
+                println!("  🔒 Reusing existing dev cert at .hotplate/certs/");
+                return Ok((cert_path, key_path));
+            }
+            Some(reason) => println!("  🔄 Regenerating dev cert: {}", reason),
+        }
+    }
+
+    // Create .hotplate/certs directory
+    std::fs::create_dir_all(&cert_dir)
+        .with_context(|| format!("Failed to create directory: {}", cert_dir.display()))?;
+
+    let mut params = rcgen::CertificateParams::new(vec![
+        "localhost".to_string(),
+    ])?;
+    params.distinguished_name.push(
+        rcgen::DnType::CommonName,
+        rcgen::DnValue::Utf8String("Hotplate Dev Server".to_string()),
+    );
+    params.distinguished_name.push(
+        rcgen::DnType::OrganizationName,
+        rcgen::DnValue::Utf8String("Hotplate".to_string()),
+    );
+    params.subject_alt_names = sans
+        .iter()
+        .map(|san| match san.parse::<std::net::IpAddr>() {
+            Ok(ip) => Ok(rcgen::SanType::IpAddress(ip)),
+            Err(_) => Ok(rcgen::SanType::DnsName(san.clone().try_into()?)),
+        })
+        .collect::<Result<_>>()?;
+
+    let now = time::OffsetDateTime::now_utc();
+    // Backdate a little to tolerate clock skew between devices
+    params.not_before = now - time::Duration::days(1);
+    params.not_after = now + time::Duration::days(LEAF_VALIDITY_DAYS);
+    params.is_ca = rcgen::IsCa::ExplicitNoCa;
+    params.key_usages = vec![
+        rcgen::KeyUsagePurpose::DigitalSignature,
+        rcgen::KeyUsagePurpose::KeyEncipherment,
+    ];
+    params.extended_key_usages = vec![rcgen::ExtendedKeyUsagePurpose::ServerAuth];
+    params.use_authority_key_identifier_extension = true;
+
+    let key_pair = rcgen::KeyPair::generate()?;
+    let cert = params.signed_by(&key_pair, &ca.cert, &ca.key)?;
+
+    // Write PEM files
+    std::fs::write(&cert_path, cert.pem())
+        .with_context(|| format!("Failed to write cert: {}", cert_path.display()))?;
+    write_private(&key_path, &key_pair.serialize_pem())?;
+    std::fs::write(&hosts_path, wanted_hosts.iter().map(|h| format!("{}\n", h)).collect::<String>())
+        .with_context(|| format!("Failed to write {}", hosts_path.display()))?;
+
+    println!("  🔒 Generated dev certificate in .hotplate/certs/ (signed by the local CA):");
+    println!("     📄 {}", cert_path.display());
+    println!("     🔑 {}", key_path.display());
+
+    Ok((cert_path, key_path))
+}
+
+/// Subject Alternative Names for the dev cert: the loopback names, the LAN IP
+/// (so mobile devices can connect without warnings) and the configured `cert_hosts`.
+fn dev_cert_sans(hosts: &[String]) -> Vec<String> {
+    let mut sans = vec!["localhost".to_string(), "127.0.0.1".to_string(), "::1".to_string()];
+    if let Some(lan_ip) = get_lan_ip() {
+        sans.push(lan_ip.to_string());
+    }
+    for host in hosts {
+        let host = host.trim().to_ascii_lowercase();
+        if !sans.contains(&host) {
+            sans.push(host);
+        }
+    }
+    sans
+}
+
+/// Check a `--cert-host` value: a DNS name or IP, optionally with a leading `*.` wildcard.
+pub(crate) fn validate_cert_host(host: &str) -> Result<()> {
+    let host = host.trim();
+    if host.parse::<std::net::IpAddr>().is_ok() {
+        return Ok(());
+    }
+    let name = host.strip_prefix("*.").unwrap_or(host);
+    let valid = !name.is_empty()
+        && name.len() <= 253
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    anyhow::ensure!(
+        valid,
+        "Invalid cert host '{}' — expected a hostname like \"myapp.test\" or \"*.myapp.test\"",
+        host
+    );
+    Ok(())
+}
+
+/// Why the existing dev cert would be regenerated on the next start, if at all.
+/// Read-only: doesn't create the CA.
+pub(crate) fn dev_cert_stale_reason(workspace: &Path, hosts: &[String]) -> Option<String> {
+    let (cert_path, _) = dev_cert_paths(workspace);
+    let (ca_cert_path, _) = ca_paths(workspace);
+    let Ok(ca) = read_cert_info(&ca_cert_path) else {
+        return Some("local CA not created yet".into());
+    };
+    match read_cert_info(&cert_path) {
+        Ok(info) => stale_reason(&info, &ca.subject, &dev_cert_sans(hosts)).or_else(|| {
+            hosts_changed(&read_cert_hosts(&dev_cert_hosts_path(workspace)), &normalize_cert_hosts(hosts))
+        }),
+        Err(e) => Some(format!("{:#}", e)),
+    }
+}
+
+/// Why an existing leaf cert must be regenerated, or `None` if it can be reused.
+fn stale_reason(info: &CertInfo, ca_subject: &str, sans: &[String]) -> Option<String> {
+    if info.issuer != ca_subject {
+        return Some("not issued by the local CA".into());
+    }
+    if info.expires_within(RENEW_BEFORE_DAYS) {
+        return Some(format!("expires {}", info.not_after));
+    }
+    // An extra LAN IP is harmless (e.g. while offline), only missing SANs count here;
+    // removed `cert_hosts` are caught by `hosts_changed`
+    let missing: Vec<&str> = sans.iter().filter(|s| !info.sans.contains(s)).map(String::as_str).collect();
+    if !missing.is_empty() {
+        return Some(format!("{} not in SANs", missing.join(", ")));
+    }
+    None
+}
+
+/// `cert_hosts` as compared between runs: trimmed, lowercased, sorted, without
+/// the loopback names every dev cert has anyway.
+fn normalize_cert_hosts(hosts: &[String]) -> Vec<String> {
+    let mut hosts: Vec<String> = hosts
+        .iter()
+        .map(|h| h.trim().to_ascii_lowercase())
+        .filter(|h| !h.is_empty() && !["localhost", "127.0.0.1", "::1"].contains(&h.as_str()))
+        .collect();
+    hosts.sort();
+    hosts.dedup();
+    hosts
+}
+
+/// The `cert_hosts` recorded when the dev cert was generated (none if the file is missing).
+fn read_cert_hosts(path: &Path) -> Vec<String> {
+    let hosts: Vec<String> = std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .map(String::from)
+        .collect();
+    normalize_cert_hosts(&hosts)
+}
+
+/// Why the cert must be regenerated for a different `cert_hosts` list, if it must.
+fn hosts_changed(recorded: &[String], wanted: &[String]) -> Option<String> {
+    let removed: Vec<&str> = recorded.iter().filter(|h| !wanted.contains(h)).map(String::as_str).collect();
+    (!removed.is_empty()).then(|| format!("{} no longer in cert_hosts", removed.join(", ")))
+}
+
+// ───────────────────── Local CA ─────────────────────
+
+struct LocalCa {
+    /// Issuer for signing — re-created from the stored params, never written to disk.
+    cert: rcgen::Certificate,
+    key: rcgen::KeyPair,
+    /// Subject DN as printed by [`read_cert_info`], to match leaf issuers against.
+    subject: String,
+}
+
+/// Load the local CA from `.hotplate/certs/ca/`, creating it on first use.
+fn load_or_create_ca(workspace: &Path) -> Result<LocalCa> {
+    let (ca_cert_path, ca_key_path) = ca_paths(workspace);
+
+    if ca_cert_path.exists() && ca_key_path.exists() {
+        let cert_pem = std::fs::read_to_string(&ca_cert_path)
+            .with_context(|| format!("Failed to read CA: {}", ca_cert_path.display()))?;
+        let key_pem = std::fs::read_to_string(&ca_key_path)
+            .with_context(|| format!("Failed to read CA key: {}", ca_key_path.display()))?;
+        let key = rcgen::KeyPair::from_pem(&key_pem)
+            .with_context(|| format!("Invalid CA key: {}", ca_key_path.display()))?;
+        let params = rcgen::CertificateParams::from_ca_cert_pem(&cert_pem)
+            .with_context(|| format!("Invalid CA cert: {}", ca_cert_path.display()))?;
+        let subject = read_cert_info(&ca_cert_path)?.subject;
+        let cert = params.self_signed(&key)?;
+        return Ok(LocalCa { cert, key, subject });
+    }
+
+    let ca_dir = ca_cert_path.parent().unwrap_or(workspace);
+    std::fs::create_dir_all(ca_dir)
+        .with_context(|| format!("Failed to create directory: {}", ca_dir.display()))?;
+
+    // Name the CA after the workspace so several trusted Hotplate CAs can be told apart
+    let name = workspace
+        .canonicalize()
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "workspace".into());
+
+    let mut params = rcgen::CertificateParams::default();
+    params.distinguished_name.push(
+        rcgen::DnType::CommonName,
+        rcgen::DnValue::Utf8String(format!("Hotplate Local CA ({})", name)),
+    );
+    params.distinguished_name.push(
+        rcgen::DnType::OrganizationName,
+        rcgen::DnValue::Utf8String("Hotplate".to_string()),
+    );
+    let now = time::OffsetDateTime::now_utc();
+    params.not_before = now - time::Duration::days(1);
+    params.not_after = now + time::Duration::days(CA_VALIDITY_DAYS);
+    params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Constrained(0));
+    params.key_usages = vec![
+        rcgen::KeyUsagePurpose::KeyCertSign,
+        rcgen::KeyUsagePurpose::CrlSign,
+        rcgen::KeyUsagePurpose::DigitalSignature,
+    ];
+
+    let key = rcgen::KeyPair::generate()?;
+    let cert = params.self_signed(&key)?;
+
+    std::fs::write(&ca_cert_path, cert.pem())
+        .with_context(|| format!("Failed to write CA: {}", ca_cert_path.display()))?;
+    write_private(&ca_key_path, &key.serialize_pem())?;
+
+    println!("  🏛  Created local CA in .hotplate/certs/ca/");
+    println!("     Trust it once per device: hotplate cert export-ca");
+
+    let subject = read_cert_info(&ca_cert_path)?.subject;
+    Ok(LocalCa { cert, key, subject })
+}
+
+/// `hotplate cert export-ca` — write the CA cert as `hotplate-ca.pem` and
+/// `hotplate-ca.der` (for Android / Windows) into `out_dir`.
+pub(crate) fn export_ca(workspace: &Path, out_dir: &Path) -> Result<()> {
+    load_or_create_ca(workspace)?;
+    let (ca_cert_path, _) = ca_paths(workspace);
+
+    let pem = std::fs::read(&ca_cert_path)
+        .with_context(|| format!("Failed to read CA: {}", ca_cert_path.display()))?;
+    let (_, parsed) = x509_parser::pem::parse_x509_pem(&pem)
+        .map_err(|e| anyhow::anyhow!("Invalid PEM in {}: {}", ca_cert_path.display(), e))?;
+
+    std::fs::create_dir_all(out_dir)
+        .with_context(|| format!("Failed to create directory: {}", out_dir.display()))?;
+    let pem_out = out_dir.join("hotplate-ca.pem");
+    let der_out = out_dir.join("hotplate-ca.der");
+    std::fs::write(&pem_out, &pem).with_context(|| format!("Failed to write {}", pem_out.display()))?;
+    std::fs::write(&der_out, &parsed.contents)
+        .with_context(|| format!("Failed to write {}", der_out.display()))?;
+
+    println!("  🏛  Exported local CA:");
+    println!("     📄 {}", pem_out.display());
+    println!("     📄 {}", der_out.display());
+    println!();
+    println!("  Install it as a trusted root:");
+    println!("     macOS    sudo security add-trusted-cert -d -r trustRoot -k /Library/Keychains/System.keychain hotplate-ca.pem");
+    println!("     Linux    sudo cp hotplate-ca.pem /usr/local/share/ca-certificates/hotplate-ca.crt && sudo update-ca-certificates");
+    println!("     Windows  certutil -addstore -f ROOT hotplate-ca.der");
+    println!("     iOS      AirDrop hotplate-ca.pem, install the profile, then enable it in");
+    println!("              Settings → General → About → Certificate Trust Settings");
+    println!("     Android  Settings → Security → Encryption & credentials → Install a certificate → CA certificate");
+    Ok(())
+}
+
+/// Write a private key, readable only by the owner on Unix.
+fn write_private(path: &Path, pem: &str) -> Result<()> {
+    std::fs::write(path, pem).with_context(|| format!("Failed to write key: {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600));
+    }
+    Ok(())
+}
+
+/// Detect LAN IPv4 address (same logic used in server.rs banner)
+pub(crate) fn get_lan_ip() -> Option<std::net::IpAddr> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    socket.local_addr().ok().map(|a| a.ip())
+}
+
+// ───────────────────── Inspection ─────────────────────
+
+/// Summary of a PEM certificate, as printed by `hotplate cert show`.
+#[derive(Debug, Clone)]
+pub(crate) struct CertInfo {
+    pub subject: String,
+    pub issuer: String,
+    /// DNS names and IP addresses from the subjectAltName extension.
+    pub sans: Vec<String>,
+    pub not_before: String,
+    pub not_after: String,
+    /// Unix timestamp of `not_after`.
+    pub not_after_ts: i64,
+}
+
+impl CertInfo {
+    pub fn is_expired(&self) -> bool {
+        self.expires_within(0)
+    }
+
+    /// Whether the cert expires in less than `days` days (or already has).
+    pub fn expires_within(&self, days: i64) -> bool {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        self.not_after_ts < now + days * 86_400
+    }
+}
+
+/// Parse the first certificate of a PEM file.
+pub(crate) fn read_cert_info(path: &Path) -> Result<CertInfo> {
+    use x509_parser::extensions::GeneralName;
+
+    let data = std::fs::read(path).with_context(|| format!("Failed to read cert: {}", path.display()))?;
+    let (_, pem) = x509_parser::pem::parse_x509_pem(&data)
+        .map_err(|e| anyhow::anyhow!("Invalid PEM in {}: {}", path.display(), e))?;
+    let cert = pem
+        .parse_x509()
+        .map_err(|e| anyhow::anyhow!("Invalid certificate in {}: {}", path.display(), e))?;
+
+    let sans = cert
+        .subject_alternative_name()
+        .ok()
+        .flatten()
+        .map(|ext| {
+            ext.value
+                .general_names
+                .iter()
+                .filter_map(|name| match name {
+                    GeneralName::DNSName(dns) => Some(dns.to_string()),
+                    GeneralName::IPAddress(bytes) => ip_from_bytes(bytes).map(|ip| ip.to_string()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let validity = cert.validity();
+    Ok(CertInfo {
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        sans,
+        not_before: validity.not_before.to_string(),
+        not_after: validity.not_after.to_string(),
+        not_after_ts: validity.not_after.timestamp(),
+    })
+}
+
+fn ip_from_bytes(bytes: &[u8]) -> Option<std::net::IpAddr> {
+    match bytes.len() {
+        4 => Some(std::net::IpAddr::from(<[u8; 4]>::try_from(bytes).ok()?)),
+        16 => Some(std::net::IpAddr::from(<[u8; 16]>::try_from(bytes).ok()?)),
+        _ => None,
+    }
+}
+
+/// `hotplate cert show` — print the auto-generated cert's details.
+pub(crate) fn show(workspace: &Path) -> Result<()> {
+    let (cert_path, key_path) = dev_cert_paths(workspace);
+    anyhow::ensure!(
+        cert_path.exists(),
+        "No certificate at {} — run `hotplate cert create`",
+        cert_path.display()
+    );
+    let info = read_cert_info(&cert_path)?;
+
+    println!("  📄 Cert:     {}", cert_path.display());
+    println!(
+        "  🔑 Key:      {}{}",
+        key_path.display(),
+        if key_path.exists() { "" } else { " (missing)" }
+    );
+    println!("  👤 Subject:  {}", info.subject);
+    println!("  🏛  Issuer:   {}", info.issuer);
+    println!("  🌐 SANs:     {}", info.sans.join(", "));
+    println!("  📅 Valid:    {} → {}", info.not_before, info.not_after);
+    if info.is_expired() {
+        println!("  ⚠  Expired — run `hotplate cert regenerate`");
+    }
+
+    let (ca_cert_path, _) = ca_paths(workspace);
+    if let Ok(ca) = read_cert_info(&ca_cert_path) {
+        println!();
+        println!("  🏛  CA:       {}", ca_cert_path.display());
+        println!("  📅 Valid:    {} → {}", ca.not_before, ca.not_after);
+        if ca.subject != info.issuer {
+            println!("  ⚠  Cert was not issued by this CA — run `hotplate cert regenerate`");
+        }
+    }
+    Ok(())
+}
+
+/// `hotplate cert regenerate` — delete the leaf cert and sign a new one.
+/// The CA is kept, so devices that trust it keep working.
+pub(crate) fn regenerate(workspace: &Path, hosts: &[String]) -> Result<(PathBuf, PathBuf)> {
+    let (cert_path, key_path) = dev_cert_paths(workspace);
+    for path in [&cert_path, &key_path] {
+        if path.exists() {
+            std::fs::remove_file(path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+    }
+    generate_dev_cert(workspace, hosts)
+}
+
+// ───────────────────── Loading ─────────────────────
+
+/// Build the rustls config for the server from PEM files, decrypting a
+/// passphrase-protected PKCS#8 key (`BEGIN ENCRYPTED PRIVATE KEY`) first.
+/// With `interactive` (and a terminal), a missing passphrase is prompted for
+/// and stored in `passphrase`, so later reloads can reuse it.
+pub(crate) fn load_rustls_config(
+    cert: &Path,
+    key: &Path,
+    client_ca: Option<&Path>,
+    passphrase: &mut Option<String>,
+    interactive: bool,
+) -> Result<axum_server::tls_rustls::RustlsConfig> {
+    if passphrase.is_none() && interactive && std::io::stdin().is_terminal() && is_encrypted_key(key) {
+        let p = rpassword::prompt_password(format!("  🔑 Passphrase for {}: ", key.display()))
+            .context("Failed to read passphrase")?;
+        *passphrase = Some(p);
+    }
+    let server_config = server_config(cert, key, client_ca, passphrase.as_deref())?;
+    Ok(axum_server::tls_rustls::RustlsConfig::from_config(server_config))
+}
+
+/// rustls server config for the cert and (decrypted) key. ALPN offers `h2`
+/// first, so browsers multiplex all requests over one HTTP/2 connection.
+/// With `client_ca`, every client must present a cert signed by one of its CAs (mTLS).
+pub(crate) fn server_config(
+    cert: &Path,
+    key: &Path,
+    client_ca: Option<&Path>,
+    passphrase: Option<&str>,
+) -> Result<Arc<ServerConfig>> {
+    let cert_pem = std::fs::read(cert).with_context(|| format!("Failed to read cert: {}", cert.display()))?;
+    let key_pem = read_private_key(key, passphrase)?;
+    let invalid = || format!("Invalid TLS cert/key: {} / {}", cert.display(), key.display());
+
+    let chain = CertificateDer::pem_slice_iter(&cert_pem)
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(invalid)?;
+    anyhow::ensure!(!chain.is_empty(), "No certificate found in {}", cert.display());
+    let key_der = PrivateKeyDer::from_pem_slice(&key_pem).with_context(invalid)?;
+
+    let builder = match client_ca {
+        Some(ca) => ServerConfig::builder().with_client_cert_verifier(client_verifier(ca)?),
+        None => ServerConfig::builder().with_no_client_auth(),
+    };
+    let mut config = builder
+        .with_single_cert(chain, key_der)
+        .with_context(invalid)?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(Arc::new(config))
+}
+
+/// Verifier requiring a client cert issued by one of the CAs in the `ca` PEM bundle.
+fn client_verifier(ca: &Path) -> Result<Arc<dyn rustls::server::danger::ClientCertVerifier>> {
+    let pem = std::fs::read(ca).with_context(|| format!("Failed to read client CA: {}", ca.display()))?;
+    let mut roots = RootCertStore::empty();
+    for der in CertificateDer::pem_slice_iter(&pem) {
+        let der = der.with_context(|| format!("Invalid client CA: {}", ca.display()))?;
+        roots
+            .add(der)
+            .with_context(|| format!("Invalid client CA: {}", ca.display()))?;
+    }
+    anyhow::ensure!(!roots.is_empty(), "No certificate found in client CA {}", ca.display());
+    WebPkiClientVerifier::builder(Arc::new(roots))
+        .build()
+        .with_context(|| format!("Invalid client CA: {}", ca.display()))
+}
+
+/// Subject DN of a (verified) client cert, e.g. `CN=alice, O=Staging`.
+pub(crate) fn cert_subject(der: &[u8]) -> Option<String> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der).ok()?;
+    Some(cert.subject().to_string())
+}
+
+fn is_encrypted_key(path: &Path) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|pem| pem.contains("BEGIN ENCRYPTED PRIVATE KEY"))
+}
+
+/// Read a PEM private key, returning an unencrypted PEM.
+pub(crate) fn read_private_key(path: &Path, passphrase: Option<&str>) -> Result<Vec<u8>> {
+    let pem = std::fs::read_to_string(path).with_context(|| format!("Failed to read key: {}", path.display()))?;
+
+    if pem.contains("Proc-Type: 4,ENCRYPTED") {
+        anyhow::bail!(
+            "{} uses legacy OpenSSL encryption, which is not supported — convert it with \
+             `openssl pkcs8 -topk8 -in {} -out key.pem`",
+            path.display(),
+            path.display()
+        );
+    }
+    if !pem.contains("BEGIN ENCRYPTED PRIVATE KEY") {
+        return Ok(pem.into_bytes());
+    }
+
+    let Some(passphrase) = passphrase else {
+        anyhow::bail!(
+            "{} is encrypted — set hotplate.https.passphrase in settings.json or HOTPLATE_KEY_PASSPHRASE",
+            path.display()
+        );
+    };
+    decrypt_private_key(&pem, passphrase).with_context(|| format!("Failed to decrypt {}", path.display()))
+}
+
+fn decrypt_private_key(pem: &str, passphrase: &str) -> Result<Vec<u8>> {
+    use pkcs8::der::pem::LineEnding;
+    use pkcs8::pkcs5;
+
+    let (_, doc) = pkcs8::SecretDocument::from_pem(pem).map_err(|e| anyhow::anyhow!("Invalid PEM: {}", e))?;
+    let unsupported = |e: &dyn std::fmt::Display| {
+        anyhow::anyhow!("Unsupported key encryption algorithm ({}) — re-encrypt with PBES2/AES", e)
+    };
+    // Schemes pkcs5 doesn't know (PKCS#12 PBE, 3DES, Camellia...) already fail on their OID here
+    let info = pkcs8::EncryptedPrivateKeyInfo::try_from(doc.as_bytes()).map_err(|e| match e {
+        pkcs8::Error::Asn1(ref der) if is_oid_error(der.kind()) => unsupported(&e),
+        _ => anyhow::anyhow!("Invalid encrypted PKCS#8 key: {}", e),
+    })?;
+    // A wrong passphrase surfaces as a padding / ASN.1 error after decryption
+    let key = info.decrypt(passphrase).map_err(|e| match e {
+        pkcs8::Error::EncryptedPrivateKey(
+            ref e @ (pkcs5::Error::NoPbes1CryptSupport
+            | pkcs5::Error::UnsupportedAlgorithm { .. }
+            | pkcs5::Error::AlgorithmParametersInvalid { .. }),
+        ) => unsupported(e),
+        _ => anyhow::anyhow!("Wrong passphrase"),
+    })?;
+    let pem = key
+        .to_pem("PRIVATE KEY", LineEnding::LF)
+        .map_err(|e| anyhow::anyhow!("Failed to encode key: {}", e))?;
+    Ok(pem.as_bytes().to_vec())
+}
+
+/// Whether a DER error comes from an algorithm OID the parser doesn't recognize.
+fn is_oid_error(kind: pkcs8::der::ErrorKind) -> bool {
+    use pkcs8::der::{ErrorKind, Tag};
+    matches!(kind, ErrorKind::OidUnknown { .. } | ErrorKind::Value { tag: Tag::ObjectIdentifier })
+}
@@ -0,0 +1,350 @@
+//! `hotplate doctor` — check the environment and configuration.
+//!
+//! Resolves the config the same way `serve` does (without writing anything)
+//! and walks the usual "live reload doesn't work" checklist: settings.json,
+//! project config, root, port, TLS cert, file watcher, ignore globs, mounts.
+//!
+//! Usage:
+//!   hotplate doctor                 # human-readable report
+//!   hotplate doctor --json          # machine-readable report for scripts
+//!   hotplate doctor --profile lan   # accepts every `serve` option
+
+use crate::project::ConfigSource;
+use crate::{cert, events::EventLogger, watcher, Config, DoctorArgs};
+use anyhow::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+}
+
+#[derive(Default)]
+struct Report {
+    checks: Vec<Check>,
+}
+
+impl Report {
+    fn add(&mut self, name: &'static str, status: Status, message: impl Into<String>) -> &mut Check {
+        self.checks.push(Check { name, status, message: message.into(), hint: None });
+        self.checks.last_mut().expect("just pushed")
+    }
+
+    fn pass(&mut self, name: &'static str, message: impl Into<String>) {
+        self.add(name, Status::Pass, message);
+    }
+
+    fn warn(&mut self, name: &'static str, message: impl Into<String>, hint: impl Into<String>) {
+        self.add(name, Status::Warn, message).hint = Some(hint.into());
+    }
+
+    fn fail(&mut self, name: &'static str, message: impl Into<String>, hint: impl Into<String>) {
+        self.add(name, Status::Fail, message).hint = Some(hint.into());
+    }
+
+    fn count(&self, status: Status) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+}
+
+pub(crate) fn run(args: DoctorArgs) -> Result<()> {
+    let mut report = Report::default();
+    let workspace = crate::workspace_dir(args.serve.workspace.clone());
+
+    check_vscode_settings(&mut report, &workspace);
+    match crate::resolve_config(args.serve, true) {
+        Ok(config) => {
+            check_config(&mut report, &config);
+            check_root(&mut report, &config);
+            check_port(&mut report, &config);
+            check_tls(&mut report, &config);
+            check_watcher(&mut report, &config);
+            check_ignore(&mut report, &config);
+            check_mounts(&mut report, &config);
+        }
+        Err(e) => report.fail(
+            "config",
+            format!("{:#}", e),
+            "Fix the config error above; the remaining checks need a valid config",
+        ),
+    }
+
+    let (passed, warned, failed) =
+        (report.count(Status::Pass), report.count(Status::Warn), report.count(Status::Fail));
+
+    if args.json {
+        let out = serde_json::json!({
+            "ok": failed == 0,
+            "workspace": workspace,
+            "summary": { "pass": passed, "warn": warned, "fail": failed },
+            "checks": report.checks,
+        });
+        println!("{}", serde_json::to_string_pretty(&out)?);
+    } else {
+        println!();
+        println!("  🩺 hotplate doctor — {}", workspace.display());
+        println!();
+        for check in &report.checks {
+            let icon = match check.status {
+                Status::Pass => "✓",
+                Status::Warn => "⚠",
+                Status::Fail => "✗",
+            };
+            println!("  {} {:<10} {}", icon, check.name, check.message);
+            if let Some(ref hint) = check.hint {
+                println!("    {:<10} → {}", "", hint);
+            }
+        }
+        println!();
+        println!("  {} passed, {} warnings, {} failed", passed, warned, failed);
+        println!();
+    }
+
+    anyhow::ensure!(failed == 0, "doctor found {} problem(s)", failed);
+    Ok(())
+}
+
+/// `.vscode/settings.json` is optional, but a broken one is silently skipped by the server.
+fn check_vscode_settings(report: &mut Report, workspace: &Path) {
+    let path = workspace.join(".vscode").join("settings.json");
+    match crate::parse_vscode_settings(&path) {
+        Ok(Some(_)) => report.pass("settings", format!("{} is valid JSONC", path.display())),
+        Ok(None) => report.pass("settings", "No .vscode/settings.json (optional)"),
+        Err(e) => report.fail(
+            "settings",
+            format!("{:#}", e),
+            "The server ignores this file until it parses — check for missing commas or quotes",
+        ),
+    }
+}
+
+fn check_config(report: &mut Report, config: &Config) {
+    let file = match config.config_file {
+        Some(ref f) => format!("Loaded {}", f.display()),
+        None => "No project config (hotplate.toml), using CLI/env/settings.json".into(),
+    };
+    match config.profile {
+        Some(ref p) => report.pass("config", format!("{} (profile: {})", file, p)),
+        None => report.pass("config", file),
+    }
+}
+
+fn check_root(report: &mut Report, config: &Config) {
+    if !config.root.is_dir() {
+        report.fail(
+            "root",
+            format!("{} is not a directory", config.root.display()),
+            "Set --root or `root` in hotplate.toml to the folder containing your HTML",
+        );
+    } else if config.spa_file.is_none() && !config.root.join("index.html").is_file() {
+        report.warn(
+            "root",
+            format!("{} has no index.html", config.root.display()),
+            if config.dir_listing {
+                "Is --root pointing at the right folder? `/` will show a directory listing"
+            } else {
+                "Is --root pointing at the right folder? `/` will show the Hotplate welcome page"
+            },
+        );
+    } else {
+        report.pass("root", config.root.display().to_string());
+    }
+}
+
+fn check_port(report: &mut Report, config: &Config) {
+    let addr = format!("{}:{}", config.host, config.port);
+    match std::net::TcpListener::bind((config.host.as_str(), config.port)) {
+        Ok(_) => report.pass("port", format!("{} is free", addr)),
+        Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => report.warn(
+            "port",
+            format!("{} is in use", addr),
+            "The server will pick the next free port; stop the other process to keep URLs stable",
+        ),
+        Err(e) => report.fail("port", format!("Cannot bind {}: {}", addr, e), "Choose another --host/--port"),
+    }
+}
+
+fn check_tls(report: &mut Report, config: &Config) {
+    let (Some(ref cert_path), Some(ref key_path)) = (&config.cert, &config.key) else {
+        report.pass("tls", "HTTPS disabled");
+        return;
+    };
+
+    if !cert_path.exists() {
+        if config.sources.get("cert") == Some(&ConfigSource::Generated) {
+            report.pass("tls", format!("Dev cert will be generated at {}", cert_path.display()));
+        } else {
+            report.fail("tls", format!("Cert not found: {}", cert_path.display()), "Check --cert / `cert`");
+        }
+        return;
+    }
+    if !key_path.exists() {
+        report.fail("tls", format!("Key not found: {}", key_path.display()), "Check --key / `key`");
+        return;
+    }
+    if let Err(e) = cert::read_private_key(key_path, config.key_passphrase.as_deref()) {
+        report.fail(
+            "tls",
+            format!("{:#}", e),
+            "The server cannot start HTTPS until the key can be read",
+        );
+        return;
+    }
+
+    // A stale dev cert is replaced automatically on start
+    if config.sources.get("cert") == Some(&ConfigSource::Generated) {
+        if let Some(reason) = cert::dev_cert_stale_reason(&config.workspace, &config.cert_hosts) {
+            report.pass("tls", format!("Dev cert will be regenerated on start ({})", reason));
+            return;
+        }
+    }
+
+    let info = match cert::read_cert_info(cert_path) {
+        Ok(info) => info,
+        Err(e) => {
+            report.fail("tls", format!("{:#}", e), "Use a PEM-encoded X.509 certificate");
+            return;
+        }
+    };
+    if info.is_expired() {
+        report.fail(
+            "tls",
+            format!("{} expired on {}", cert_path.display(), info.not_after),
+            "Run `hotplate cert regenerate`",
+        );
+        return;
+    }
+
+    // Phones on the LAN connect via the LAN IP — it must be in the SANs
+    let lan_ip = cert::get_lan_ip().map(|ip| ip.to_string());
+    match lan_ip {
+        Some(ip) if !info.sans.contains(&ip) => report.warn(
+            "tls",
+            format!("{} does not cover LAN IP {} (SANs: {})", cert_path.display(), ip, info.sans.join(", ")),
+            "Run `hotplate cert regenerate` so other devices can connect without warnings",
+        ),
+        _ => report.pass(
+            "tls",
+            format!("{} valid until {} (SANs: {})", cert_path.display(), info.not_after, info.sans.join(", ")),
+        ),
+    }
+}
+
+fn check_watcher(report: &mut Report, config: &Config) {
+    if !config.live_reload {
+        report.pass("watcher", "Live reload disabled");
+        return;
+    }
+    if !config.root.is_dir() {
+        return; // already reported by the root check
+    }
+
+    // Same setup as `serve`, so the OS limits are hit the same way
+    let (tx, _rx) = tokio::sync::broadcast::channel(1);
+    if let Err(e) = watcher::spawn(
+        config.root.clone(),
+        tx,
+        &config.ignore_patterns,
+        &config.watch_extensions,
+        EventLogger::noop(),
+    ) {
+        report.fail("watcher", format!("Cannot watch {}: {}", config.root.display(), e), WATCH_LIMIT_HINT);
+        return;
+    }
+
+    match inotify_watch_limit() {
+        Some(limit) => {
+            let dirs = count_dirs(&config.root, limit);
+            if dirs >= limit {
+                report.fail(
+                    "watcher",
+                    format!("{}+ directories under root exceed the inotify limit ({})", dirs, limit),
+                    WATCH_LIMIT_HINT,
+                );
+            } else if dirs * 5 >= limit * 4 {
+                report.warn(
+                    "watcher",
+                    format!("{} directories under root, close to the inotify limit ({})", dirs, limit),
+                    WATCH_LIMIT_HINT,
+                );
+            } else {
+                report.pass("watcher", format!("{} directories watched (inotify limit {})", dirs, limit));
+            }
+        }
+        None => report.pass("watcher", format!("Watching {}", config.root.display())),
+    }
+}
+
+const WATCH_LIMIT_HINT: &str =
+    "Raise it: sudo sysctl fs.inotify.max_user_watches=524288 (or narrow --root)";
+
+/// `fs.inotify.max_user_watches` (Linux only).
+fn inotify_watch_limit() -> Option<usize> {
+    std::fs::read_to_string("/proc/sys/fs/inotify/max_user_watches")
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Number of directories under `root` (inotify needs one watch each), stopping at `cap`.
+fn count_dirs(root: &Path, cap: usize) -> usize {
+    let mut count = 0;
+    let mut stack: Vec<PathBuf> = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        count += 1;
+        if count >= cap {
+            break;
+        }
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            // file_type() doesn't follow symlinks, matching the watcher
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                stack.push(entry.path());
+            }
+        }
+    }
+    count
+}
+
+fn check_ignore(report: &mut Report, config: &Config) {
+    let invalid: Vec<String> = config
+        .ignore_patterns
+        .iter()
+        .filter_map(|p| globset::Glob::new(p).err().map(|e| format!("'{}': {}", p, e)))
+        .collect();
+    if invalid.is_empty() {
+        if !config.ignore_patterns.is_empty() {
+            report.pass("ignore", format!("{} pattern(s) valid", config.ignore_patterns.len()));
+        }
+    } else {
+        report.warn("ignore", invalid.join("; "), "Invalid patterns are skipped by the watcher");
+    }
+}
+
+fn check_mounts(report: &mut Report, config: &Config) {
+    for (url_path, fs_path) in &config.mounts {
+        if fs_path.exists() {
+            report.pass("mount", format!("{} → {}", url_path, fs_path.display()));
+        } else {
+            report.fail(
+                "mount",
+                format!("{} → {} does not exist", url_path, fs_path.display()),
+                "Requests under this URL will 404 until the path exists",
+            );
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Environment variable overrides — every CLI option as `HOTPLATE_<OPTION>`.
+//!
+//! The variable name is the long flag upper-cased with `-` → `_`:
+//!   `--port` → `HOTPLATE_PORT`, `--proxy-target` → `HOTPLATE_PROXY_TARGET`,
+//!   `--watch-ext` → `HOTPLATE_WATCH_EXT`, `--no-reload` → `HOTPLATE_NO_RELOAD`
+//!
+//! Repeatable options take several values separated by `|` or newlines:
+//!   `HOTPLATE_HEADER="X-Frame-Options: DENY|X-Env: ci"`
+//! (use newlines for `HOTPLATE_PROXY` rules whose regex contains `|`)
+//!
+//! Boolean flags accept `1/0`, `true/false`, `yes/no`, `on/off`; `0` turns off
+//! a value set by a lower layer (`HOTPLATE_COMPRESS=0` beats `compress = true`).
+//! Empty variables are treated as unset.
+//!
+//! `HOTPLATE_KEY_PASSPHRASE` has no CLI equivalent — it's read by `build_config`
+//! to decrypt a passphrase-protected private key.
+//!
+//! Precedence: CLI > env > profile > hotplate.toml > settings.json > defaults.
+
+use crate::ServeArgs;
+use anyhow::{Context, Result};
+use std::str::FromStr;
+
+const PREFIX: &str = "HOTPLATE_";
+
+/// Read all `HOTPLATE_*` variables into [`ServeArgs`] (unset options stay at their defaults).
+pub(crate) fn from_env() -> Result<ServeArgs> {
+    Ok(ServeArgs {
+        host: var("HOST"),
+        port: parse("PORT")?,
+        root: var("ROOT"),
+        cert: var("CERT"),
+        key: var("KEY"),
+        no_reload: flag("NO_RELOAD")?,
+        full_reload: flag("FULL_RELOAD")?,
+        workspace: var("WORKSPACE"),
+        config: var("CONFIG"),
+        profile: var("PROFILE"),
+        ignore: list("IGNORE"),
+        deny: list("DENY"),
+        allow_dotfiles: flag("ALLOW_DOTFILES")?,
+        allow_ips: list("ALLOW_IP"),
+        loopback_only: flag("LOOPBACK_ONLY")?,
+        basic_auth: var("BASIC_AUTH"),
+        file: var("FILE"),
+        proxies: list("PROXY")
+            .iter()
+            .map(|r| r.parse().with_context(|| format!("{PREFIX}PROXY: invalid rule '{r}'")))
+            .collect::<Result<_>>()?,
+        proxy_base: var("PROXY_BASE"),
+        proxy_target: var("PROXY_TARGET"),
+        proxy_capture: flag("PROXY_CAPTURE")?,
+        proxy_capture_redact: list("PROXY_CAPTURE_REDACT"),
+        headers: list("HEADER"),
+        mounts: list("MOUNT"),
+        https: flag("HTTPS")?,
+        cert_hosts: list("CERT_HOST"),
+        client_ca: var("CLIENT_CA"),
+        http_port: parse("HTTP_PORT")?,
+        redirect_http: flag("REDIRECT_HTTP")?,
+        h2c: flag("H2C")?,
+        compress: flag("COMPRESS")?,
+        dir_listing: flag("DIR_LISTING")?,
+        watch_extensions: list("WATCH_EXT"),
+        no_event_log: flag("NO_EVENT_LOG")?,
+        mcp: flag("MCP")?.unwrap_or(false),
+        print_config: flag("PRINT_CONFIG")?.unwrap_or(false),
+    })
+}
+
+/// `HOTPLATE_<name>`, if set and non-empty.
+pub(crate) fn var(name: &str) -> Option<String> {
+    std::env::var(format!("{PREFIX}{name}"))
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+}
+
+/// Boolean flag — `None` when unset, so an explicit `0` still overrides lower layers.
+pub(crate) fn flag(name: &str) -> Result<Option<bool>> {
+    let Some(v) = var(name) else { return Ok(None) };
+    match v.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(Some(true)),
+        "0" | "false" | "no" | "off" => Ok(Some(false)),
+        _ => anyhow::bail!("{PREFIX}{name}: expected a boolean, got '{v}'"),
+    }
+}
+
+fn parse<T: FromStr>(name: &str) -> Result<Option<T>>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    var(name)
+        .map(|v| v.trim().parse::<T>())
+        .transpose()
+        .with_context(|| format!("{PREFIX}{name}: invalid value"))
+}
+
+fn list(name: &str) -> Vec<String> {
+    var(name)
+        .map(|v| {
+            v.split(['|', '\n'])
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
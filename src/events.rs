@@ -9,7 +9,9 @@
 //!   - HTTP request timeline
 //!   - WebSocket connection lifecycle
 
+use crate::capture::CapturedBody;
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use tokio::sync::mpsc;
 
@@ -35,6 +37,20 @@ pub enum EventData {
         uptime_secs: u64,
     },
 
+    /// The config was rebuilt after a config file changed.
+    ConfigReload {
+        path: String,
+    },
+
+    /// The TLS cert/key files changed and were reloaded (or failed to, keeping the old cert).
+    TlsReload {
+        cert: String,
+        key: String,
+        ok: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+
     /// A file was changed on disk (create/modify/remove).
     FileChange {
         path: String,
@@ -61,12 +77,60 @@ pub enum EventData {
         client_id: String,
     },
 
+    /// A WebSocket was tunneled through the proxy to its target.
+    ProxyWsConnect {
+        path: String,
+        target: String,
+        /// Subprotocol picked by the upstream (`Sec-WebSocket-Protocol`).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        protocol: Option<String>,
+    },
+
+    /// A proxied WebSocket closed (either side, or an I/O error).
+    ProxyWsDisconnect {
+        path: String,
+        duration_ms: u64,
+        /// Bytes sent browser → upstream / upstream → browser (0 after an error).
+        bytes_up: u64,
+        bytes_down: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+
+    /// A proxied exchange (`--proxy-capture`): redacted headers, truncated bodies.
+    ProxyCapture {
+        method: String,
+        path: String,
+        /// Upstream URL the request was sent to.
+        target: String,
+        status: u16, // 502/504 when the upstream failed
+        duration_ms: u64,
+        request_headers: BTreeMap<String, String>,
+        request_body: Box<CapturedBody>,
+        response_headers: BTreeMap<String, String>,
+        response_body: Box<CapturedBody>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+
     /// An HTTP request was handled.
     HttpRequest {
         method: String,
         path: String,
+        version: String, // "HTTP/1.1" | "HTTP/2.0"
         status: u16,
         duration_ms: u64,
+        /// Subject of the verified client cert (mTLS only).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        client_cert: Option<String>,
+    },
+
+    /// A request was refused by the access policy (IP allowlist, loopback-only, basic auth).
+    AccessDenied {
+        ip: String,
+        method: String,
+        path: String,
+        reason: String, // "loopback_only" | "ip_not_allowed" | "auth_required" | "bad_credentials"
     },
 
     /// A JavaScript error occurred in the browser.
@@ -294,3 +358,50 @@ impl EventLogger {
         &self.session
     }
 }
+
+// ───────────────────── Log queries ─────────────────────
+
+/// Session log files in `<workspace>/.hotplate/logs/`, oldest first.
+pub fn session_files(workspace: &Path) -> Vec<PathBuf> {
+    let log_dir = workspace.join(".hotplate").join("logs");
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&log_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            name.starts_with("events-") && name.ends_with(".jsonl")
+        })
+        .map(|e| e.path())
+        .collect();
+    files.sort();
+    files
+}
+
+/// Read a session file, keep entries of `kind` (all when `None` or `"all"`)
+/// and return `(total matching, last `limit` entries)`.
+pub fn query_events(
+    file: &Path,
+    kind: Option<&str>,
+    limit: usize,
+) -> std::io::Result<(usize, Vec<serde_json::Value>)> {
+    let content = std::fs::read_to_string(file)?;
+    let kind = kind.filter(|k| *k != "all");
+
+    let mut entries: Vec<serde_json::Value> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .filter(|entry: &serde_json::Value| match kind {
+            None => true,
+            Some(kind) => entry.get("kind").and_then(|k| k.as_str()) == Some(kind),
+        })
+        .collect();
+
+    // Take last N entries (most recent)
+    let total = entries.len();
+    if entries.len() > limit {
+        entries = entries.split_off(entries.len() - limit);
+    }
+    Ok((total, entries))
+}
@@ -2,18 +2,42 @@
 
 use axum::{
     body::Body,
-    http::{header, Request, Response},
+    http::{header, Method, Request, Response},
     middleware::Next,
 };
 use http_body_util::BodyExt;
+use std::sync::Arc;
 
 /// Live-reload + browser agent script, loaded from `src/livereload.js`.
 /// Using `include_str!` embeds the JS at compile time — zero runtime cost,
 /// and the JS file gets proper syntax highlighting & lint in the IDE.
 const RELOAD_JS: &str = include_str!("livereload.js");
 
-/// Axum middleware: if the response is HTML, inject the reload script.
-pub async fn inject_livereload(req: Request<Body>, next: Next) -> Response<Body> {
+/// Placeholder in `livereload.js` replaced by the session's `/__lr` token.
+const TOKEN_PLACEHOLDER: &str = "__HOTPLATE_LR_TOKEN__";
+
+/// Axum middleware: if the response is HTML, inject the reload script
+/// (carrying `token` for the `/__lr` handshake).
+///
+/// Page loads are requested without `Accept-Encoding`, so precompressed files
+/// and proxy upstreams answer with plain HTML we can inject into; the outer
+/// compression layer (if enabled) compresses the result.
+pub async fn inject_livereload(mut req: Request<Body>, next: Next, token: Arc<str>) -> Response<Body> {
+    // Only GET responses carry a page; HEAD must stay bodiless (HTTP/2 rejects
+    // a HEAD response with data), other methods go through untouched
+    if req.method() != Method::GET {
+        return next.run(req).await;
+    }
+
+    let wants_html = req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("text/html"));
+    if wants_html {
+        req.headers_mut().remove(header::ACCEPT_ENCODING);
+    }
+
     let resp = next.run(req).await;
 
     // Only process text/html responses
@@ -24,7 +48,13 @@ pub async fn inject_livereload(req: Request<Body>, next: Next) -> Response<Body>
         .map(|v| v.contains("text/html"))
         .unwrap_or(false);
 
-    if !is_html {
+    // Can't inject into compressed bytes (e.g. HTML fetched by a script)
+    let is_encoded = resp
+        .headers()
+        .get(header::CONTENT_ENCODING)
+        .is_some_and(|v| v != "identity");
+
+    if !is_html || is_encoded {
         return resp;
     }
 
@@ -38,7 +68,7 @@ pub async fn inject_livereload(req: Request<Body>, next: Next) -> Response<Body>
     let html = String::from_utf8_lossy(&collected);
 
     // Build <script>...</script> from the external JS file
-    let reload_script = format!("<script>\n{}\n</script>", RELOAD_JS);
+    let reload_script = format!("<script>\n{}\n</script>", RELOAD_JS.replace(TOKEN_PLACEHOLDER, &token));
 
     // Inject before </body>, or </html>, or at the end
     let injected = if let Some(pos) = html.rfind("</body>") {
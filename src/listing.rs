@@ -0,0 +1,315 @@
+//! Directory index pages — `--dir-listing`.
+//!
+//! A `GET` for a folder without `index.html` (under the root or any `--mount`)
+//! returns a sortable HTML table of its entries with sizes and modification
+//! times, or JSON when the client sends `Accept: application/json`:
+//!
+//! ```json
+//! { "path": "/data/", "entries": [
+//!   { "name": "raw", "type": "dir", "size": null, "modified": "2026-02-09T14:30:01Z" },
+//!   { "name": "points.csv", "type": "file", "size": 18230, "modified": "2026-02-09T14:28:44Z" } ] }
+//! ```
+//!
+//! Entries denied by [`PathFilter`] (`.hotplate/`, dotfiles, deny globs) or
+//! matching the ignore globs are left out. Sort with `?sort=name|size|modified`
+//! and `&order=asc|desc`; folders are always listed first.
+
+use crate::access::PathFilter;
+use axum::{
+    body::Body,
+    http::{header, request::Parts, Method, Response, StatusCode},
+};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Characters escaped in entry links (everything but unreserved URL characters).
+const LINK: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+#[derive(Serialize)]
+struct Entry {
+    name: String,
+    #[serde(rename = "type")]
+    kind: &'static str, // "dir" | "file"
+    size: Option<u64>,
+    #[serde(serialize_with = "serialize_mtime")]
+    modified: Option<SystemTime>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SortKey {
+    Name,
+    Size,
+    Modified,
+}
+
+/// Serves listings for the root and the mounts.
+pub struct DirIndex {
+    root: PathBuf,
+    /// (url_path, fs_path), longest URL prefix first.
+    mounts: Vec<(String, PathBuf)>,
+    filter: Arc<PathFilter>,
+    ignore: GlobSet,
+}
+
+impl DirIndex {
+    pub fn new(root: &Path, mounts: &[(String, PathBuf)], filter: Arc<PathFilter>, ignore: &[String]) -> Self {
+        let mut mounts = mounts.to_vec();
+        mounts.sort_by_key(|(url, _)| std::cmp::Reverse(url.len()));
+        let mut builder = GlobSetBuilder::new();
+        for pattern in ignore {
+            // Invalid patterns were already reported by the watcher
+            if let Ok(g) = Glob::new(pattern) {
+                builder.add(g);
+            }
+        }
+        Self {
+            root: root.to_path_buf(),
+            mounts,
+            filter,
+            ignore: builder.build().unwrap_or_else(|_| GlobSet::empty()),
+        }
+    }
+
+    /// The listing for `req`, or `None` if it isn't a `GET`/`HEAD` for a folder
+    /// without `index.html` (the request then goes on to the file server).
+    pub async fn respond(&self, req: &Parts) -> Option<Response<Body>> {
+        if req.method != Method::GET && req.method != Method::HEAD {
+            return None;
+        }
+        let path = req.uri.path();
+        let (base, dir) = self.resolve(path)?;
+        if !dir.is_dir() || dir.join("index.html").is_file() {
+            return None;
+        }
+
+        // Relative links need the trailing slash
+        if !path.ends_with('/') {
+            let location = match req.uri.query() {
+                Some(q) => format!("{}/?{}", path, q),
+                None => format!("{}/", path),
+            };
+            return Some(
+                Response::builder()
+                    .status(StatusCode::TEMPORARY_REDIRECT)
+                    .header(header::LOCATION, location)
+                    .body(Body::empty())
+                    .unwrap_or_default(),
+            );
+        }
+
+        let (sort, desc) = parse_sort(req.uri.query().unwrap_or(""));
+        let mut entries = self.read_entries(&base, &dir, path).await;
+        sort_entries(&mut entries, sort, desc);
+
+        let wants_json = req
+            .headers
+            .get(header::ACCEPT)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.contains("application/json"));
+        let (content_type, body) = if wants_json {
+            let json = serde_json::json!({ "path": path, "entries": entries });
+            ("application/json", json.to_string())
+        } else {
+            ("text/html; charset=utf-8", render_html(path, &entries, sort, desc))
+        };
+        Some(
+            Response::builder()
+                .header(header::CONTENT_TYPE, content_type)
+                .body(Body::from(body))
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Map a URL path to a folder on disk (mounts first), refusing `..`.
+    /// Returns the root or mount folder it lies under, and the folder itself.
+    fn resolve(&self, url_path: &str) -> Option<(PathBuf, PathBuf)> {
+        let decoded = percent_decode_str(url_path).decode_utf8().ok()?;
+        let (base, rest) = self
+            .mounts
+            .iter()
+            .find_map(|(url, fs)| {
+                let rest = decoded.strip_prefix(url.trim_end_matches('/'))?;
+                (rest.is_empty() || rest.starts_with('/')).then(|| (fs.clone(), rest.to_string()))
+            })
+            .unwrap_or_else(|| (self.root.clone(), decoded.to_string()));
+
+        let mut dir = base.clone();
+        for segment in rest.split(['/', '\\']).filter(|s| !s.is_empty() && *s != ".") {
+            if segment == ".." {
+                return None;
+            }
+            dir.push(segment);
+        }
+        Some((base, dir))
+    }
+
+    async fn read_entries(&self, base: &Path, dir: &Path, url_path: &str) -> Vec<Entry> {
+        let mut entries = Vec::new();
+        let Ok(mut read_dir) = tokio::fs::read_dir(dir).await else {
+            return entries;
+        };
+        while let Ok(Some(item)) = read_dir.next_entry().await {
+            let name = item.file_name().to_string_lossy().into_owned();
+            let entry_url = format!("{}{}", url_path, name);
+            // Ignore globs match paths relative to the root or mount folder, like the watcher
+            let path = item.path();
+            let rel = path.strip_prefix(base).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+            if self.filter.is_denied(&entry_url) || self.ignore.is_match(&rel) {
+                continue;
+            }
+            // Follow symlinks, like the file server does
+            let Ok(meta) = tokio::fs::metadata(item.path()).await else { continue };
+            entries.push(Entry {
+                name,
+                kind: if meta.is_dir() { "dir" } else { "file" },
+                size: meta.is_file().then_some(meta.len()),
+                modified: meta.modified().ok(),
+            });
+        }
+        entries
+    }
+}
+
+fn parse_sort(query: &str) -> (SortKey, bool) {
+    let mut sort = SortKey::Name;
+    let mut desc = false;
+    for (key, value) in query.split('&').filter_map(|kv| kv.split_once('=')) {
+        match (key, value) {
+            ("sort", "size") => sort = SortKey::Size,
+            ("sort", "modified") => sort = SortKey::Modified,
+            ("order", "desc") => desc = true,
+            _ => {}
+        }
+    }
+    (sort, desc)
+}
+
+fn sort_entries(entries: &mut [Entry], sort: SortKey, desc: bool) {
+    entries.sort_by(|a, b| {
+        let by_key = match sort {
+            SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortKey::Size => a.size.cmp(&b.size),
+            SortKey::Modified => a.modified.cmp(&b.modified),
+        };
+        let by_key = if desc { by_key.reverse() } else { by_key };
+        // Folders first, regardless of the order
+        (b.kind == "dir").cmp(&(a.kind == "dir")).then(by_key)
+    });
+}
+
+// ───────────────────── HTML ─────────────────────
+
+fn render_html(url_path: &str, entries: &[Entry], sort: SortKey, desc: bool) -> String {
+    let title = escape(&percent_decode_str(url_path).decode_utf8_lossy());
+    let header_link = |key: SortKey, param: &str, label: &str| {
+        // Clicking the active column flips the order
+        let next_desc = key == sort && !desc;
+        let arrow = match (key == sort, desc) {
+            (true, false) => " ↑",
+            (true, true) => " ↓",
+            _ => "",
+        };
+        format!(
+            r#"<a href="?sort={}&amp;order={}">{}{}</a>"#,
+            param,
+            if next_desc { "desc" } else { "asc" },
+            label,
+            arrow
+        )
+    };
+
+    let mut rows = String::new();
+    if url_path != "/" {
+        rows.push_str(r#"<tr><td><a href="../">📁 ../</a></td><td></td><td></td></tr>"#);
+    }
+    for e in entries {
+        let (icon, slash) = if e.kind == "dir" { ("📁", "/") } else { ("📄", "") };
+        rows.push_str(&format!(
+            r#"<tr><td><a href="{href}{slash}">{icon} {name}{slash}</a></td><td class="num">{size}</td><td class="num">{modified}</td></tr>"#,
+            href = utf8_percent_encode(&e.name, LINK),
+            name = escape(&e.name),
+            size = e.size.map(format_size).unwrap_or_default(),
+            modified = e.modified.map(|t| format_time(t, false)).unwrap_or_default(),
+        ));
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="UTF-8">
+<meta name="viewport" content="width=device-width, initial-scale=1.0">
+<title>Index of {title}</title>
+<style>
+  body {{ font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; background: #0f0f0f; color: #e0e0e0; padding: 2rem; }}
+  h1 {{ font-size: 1.1rem; font-weight: 600; margin-bottom: 1rem; }}
+  h1 span {{ color: #f59e0b; }}
+  table {{ border-collapse: collapse; width: 100%; max-width: 960px; }}
+  th, td {{ text-align: left; padding: .4rem .8rem; border-bottom: 1px solid #2a2a2a; }}
+  th a {{ color: #888; }}
+  td.num, th.num {{ text-align: right; white-space: nowrap; font-variant-numeric: tabular-nums; color: #888; }}
+  a {{ color: #e0e0e0; text-decoration: none; }}
+  a:hover {{ color: #f97316; }}
+</style>
+</head>
+<body>
+<h1>🔥 Index of <span>{title}</span></h1>
+<table>
+<thead><tr><th>{name}</th><th class="num">{size}</th><th class="num">{modified}</th></tr></thead>
+<tbody>
+{rows}
+</tbody>
+</table>
+</body>
+</html>
+"#,
+        name = header_link(SortKey::Name, "name", "Name"),
+        size = header_link(SortKey::Size, "size", "Size"),
+        modified = header_link(SortKey::Modified, "modified", "Modified"),
+    )
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Human-readable size, e.g. `18.2 KB`.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// UTC time as `2026-02-09 14:30` (or RFC 3339 for JSON).
+fn format_time(t: SystemTime, rfc3339: bool) -> String {
+    let t = time::OffsetDateTime::from(t);
+    let (date, month, day) = (t.year(), t.month() as u8, t.day());
+    if rfc3339 {
+        format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", date, month, day, t.hour(), t.minute(), t.second())
+    } else {
+        format!("{:04}-{:02}-{:02} {:02}:{:02}", date, month, day, t.hour(), t.minute())
+    }
+}
+
+fn serialize_mtime<S: serde::Serializer>(t: &Option<SystemTime>, s: S) -> Result<S::Ok, S::Error> {
+    match t {
+        Some(t) => s.serialize_str(&format_time(*t, true)),
+        None => s.serialize_none(),
+    }
+}
@@ -11,11 +11,14 @@
 //   - {kind:"console",level,msg}                    — console.warn/error
 //   - {kind:"net_error",url,method,status,error}    — failed fetch requests
 //
-// Auto-reconnects after 1s on disconnect.
+// Auto-reconnects after 1s on disconnect. The /__lr handshake needs the
+// per-session token below; if a reconnect is refused although the server is up
+// (it was restarted with a new token), the page reloads to pick up the new one.
 
 (() => {
   const p = location.protocol === "https:" ? "wss:" : "ws:";
-  let t, ws;
+  const token = "__HOTPLATE_LR_TOKEN__";
+  let t, ws, wasConnected = false;
   function send(obj) {
     try {
       if (ws && ws.readyState === 1) ws.send(JSON.stringify(obj));
@@ -119,8 +122,10 @@
       });
   };
   function connect() {
-    ws = new WebSocket(`${p}//${location.host}/__lr`);
+    ws = new WebSocket(`${p}//${location.host}/__lr?token=${token}`);
+    let opened = false;
     ws.onopen = () => {
+      opened = wasConnected = true;
       send({
         kind: "connect",
         url: location.href,
@@ -227,6 +232,16 @@
     };
     ws.onclose = () => {
       clearTimeout(t);
+      if (!opened && wasConnected) {
+        // Refused — reload if the server answers (stale token), else keep retrying.
+        // A HEAD the server or a proxy mishandles falls back to a GET probe.
+        const probe = (method) => fetch(location.href, { method, cache: "no-store" });
+        probe("HEAD")
+          .catch(() => probe("GET"))
+          .then(() => location.reload())
+          .catch(() => { t = setTimeout(connect, 1000); });
+        return;
+      }
       t = setTimeout(connect, 1000);
     };
     ws.onerror = () => ws.close();
@@ -2,35 +2,156 @@
 //!
 //! Usage:
 //!   hotplate --root ./apps --port 5500
-//!   hotplate --https                   # auto-generates self-signed cert in .hotplate/certs/
+//!   hotplate --https                   # auto-generates a CA-signed dev cert in .hotplate/certs/
 //!   hotplate --root ./apps --cert .hotplate/certs/server.crt --key .hotplate/certs/server.key
-//!   hotplate                          # auto-reads .vscode/settings.json
+//!   hotplate                          # auto-reads hotplate.toml / .vscode/settings.json
+//!
+//! Subcommands (bare `hotplate` means `serve`):
+//!   hotplate serve [OPTIONS]           # dev server
+//!   hotplate mcp                       # MCP stdio server for AI agents
+//!   hotplate cert show|create|regenerate
+//!   hotplate logs --kind js_error      # query .hotplate/logs/events-*.jsonl
+//!   hotplate doctor                    # environment diagnostics
 
+mod access;
+mod capture;
+mod cert;
+mod doctor;
+mod env;
 mod events;
 mod inject;
 #[allow(dead_code)]
 mod jsonrpc;
+mod listing;
 mod mcp;
+mod project;
+mod proxy;
 mod server;
 mod watcher;
 
 use anyhow::{Context, Result};
-use clap::Parser;
-use serde::Deserialize;
+use clap::builder::BoolishValueParser;
+use clap::{Args, Parser, Subcommand};
+use project::{ConfigSource, Layers, ProjectConfig};
+use proxy::ProxyRule;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 // ───────────────────── CLI ─────────────────────
 
 #[derive(Parser)]
-#[command(name = "hotplate", about = "⚡ Fast HTTPS live-reload dev server")]
+#[command(
+    name = "hotplate",
+    about = "⚡ Fast HTTPS live-reload dev server",
+    version,
+    args_conflicts_with_subcommands = true,
+    after_help = "Every option can also be set with a HOTPLATE_<OPTION> environment variable \
+                  (e.g. HOTPLATE_PORT, HOTPLATE_PROXY_TARGET). Repeatable options take values \
+                  separated by '|'.\nPrecedence: CLI > env > profile > hotplate.toml > \
+                  .vscode/settings.json > defaults."
+)]
 struct Cli {
-    /// Bind host
-    #[arg(long, default_value = "0.0.0.0")]
-    host: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Options for `serve` (also accepted without a subcommand)
+    #[command(flatten)]
+    serve: ServeArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the live-reload dev server (default)
+    Serve(ServeArgs),
+
+    /// Run as MCP server (stdio JSON-RPC) for AI agents
+    Mcp,
+
+    /// Manage the dev certificate and the local CA
+    Cert {
+        #[command(subcommand)]
+        action: CertAction,
+
+        /// Workspace directory [default: current directory]
+        #[arg(short, long, global = true)]
+        workspace: Option<String>,
+
+        /// Extra hostname for the cert (default: `cert_hosts` from the config)
+        #[arg(long = "cert-host", global = true)]
+        cert_hosts: Vec<String>,
+    },
+
+    /// Query the JSONL event logs in .hotplate/logs/
+    Logs(LogsArgs),
+
+    /// Check the environment and configuration
+    Doctor(DoctorArgs),
+}
+
+#[derive(Subcommand)]
+enum CertAction {
+    /// Print cert paths, subject, SANs and validity
+    Show,
+    /// Generate the cert, signed by the local CA (reuses an existing one)
+    Create,
+    /// Delete the existing cert and sign a new one (keeps the CA)
+    Regenerate,
+    /// Write the local CA as PEM and DER, to install as a trusted root on devices
+    ExportCa {
+        /// Output directory
+        #[arg(short, long, default_value = ".")]
+        out: PathBuf,
+    },
+}
+
+#[derive(Args)]
+struct LogsArgs {
+    /// Filter by event kind (e.g. http_request, js_error, file_change)
+    #[arg(short, long)]
+    kind: Option<String>,
+
+    /// Max number of entries to show (most recent)
+    #[arg(short = 'n', long, default_value_t = 100)]
+    limit: usize,
+
+    /// Session to read (e.g. 20260209-143001) [default: latest]
+    #[arg(short, long)]
+    session: Option<String>,
 
-    /// Bind port
-    #[arg(short, long, default_value_t = 5500)]
-    port: u16,
+    /// List available sessions instead of events
+    #[arg(long, default_value_t = false)]
+    list: bool,
+
+    /// Print raw JSONL instead of the summary view
+    #[arg(long, default_value_t = false)]
+    json: bool,
+
+    /// Workspace directory [default: current directory]
+    #[arg(short, long)]
+    workspace: Option<String>,
+}
+
+#[derive(Args)]
+struct DoctorArgs {
+    /// Print the report as JSON
+    #[arg(long, default_value_t = false)]
+    json: bool,
+
+    /// Same options as `serve` — checks the config they resolve to
+    #[command(flatten)]
+    serve: ServeArgs,
+}
+
+#[derive(Args, Default, Clone)]
+struct ServeArgs {
+    /// Bind host [default: 0.0.0.0]
+    #[arg(long)]
+    host: Option<String>,
+
+    /// Bind port [default: 5500]
+    #[arg(short, long)]
+    port: Option<u16>,
 
     /// Root directory to serve
     #[arg(short, long)]
@@ -45,26 +166,59 @@ struct Cli {
     key: Option<String>,
 
     /// Disable live reload
-    #[arg(long, default_value_t = false)]
-    no_reload: bool,
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, require_equals = true, default_missing_value = "true", value_parser = BoolishValueParser::new(), hide_possible_values = true)]
+    no_reload: Option<bool>,
 
     /// Force full page reload on every change (disable CSS-only hot swap)
-    #[arg(long, default_value_t = false)]
-    full_reload: bool,
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, require_equals = true, default_missing_value = "true", value_parser = BoolishValueParser::new(), hide_possible_values = true)]
+    full_reload: Option<bool>,
 
-    /// Workspace directory (for loading .vscode/settings.json)
+    /// Workspace directory (for loading hotplate.toml and .vscode/settings.json)
     #[arg(short, long)]
     workspace: Option<String>,
 
+    /// Project config file (default: hotplate.toml or .hotplate/config.toml in the workspace)
+    #[arg(short, long)]
+    config: Option<String>,
+
+    /// Named profile from the project config ([profiles.<name>])
+    #[arg(long)]
+    profile: Option<String>,
+
     /// Glob patterns of files to ignore for live reload (can be repeated)
     #[arg(long)]
     ignore: Vec<String>,
 
+    /// Glob patterns of files never to serve, 404 instead (can be repeated, e.g. "**/*.sqlite")
+    #[arg(long)]
+    deny: Vec<String>,
+
+    /// Serve dotfiles and dot-directories (.env, .git/); .hotplate/ is never served
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, require_equals = true, default_missing_value = "true", value_parser = BoolishValueParser::new(), hide_possible_values = true)]
+    allow_dotfiles: Option<bool>,
+
+    /// Only allow clients in this CIDR or IP besides loopback (can be repeated, e.g. "192.168.1.0/24")
+    #[arg(long = "allow-ip")]
+    allow_ips: Vec<String>,
+
+    /// Only allow connections from this machine (the LAN URL is shown as disabled)
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, require_equals = true, default_missing_value = "true", value_parser = BoolishValueParser::new(), hide_possible_values = true)]
+    loopback_only: Option<bool>,
+
+    /// Require HTTP Basic auth, format "user:pass" (prefer HOTPLATE_BASIC_AUTH over the command line)
+    #[arg(long, value_name = "USER:PASS")]
+    basic_auth: Option<String>,
+
     /// Serve this file for every 404 (SPA fallback, e.g. "index.html")
     #[arg(long)]
     file: Option<String>,
 
-    /// Proxy base URI (e.g. "/api")
+    /// Proxy rule (can be repeated): "/api=http://127.0.0.1:8000", options after ";"
+    /// (keep, rewrite=/v2, regex=PATTERN=>TO, keep-host, header=Name: Value, timeout=SECS)
+    #[arg(long = "proxy", value_name = "PATH=URL[;OPTION...]")]
+    proxies: Vec<ProxyRule>,
+
+    /// Proxy base URI (e.g. "/api"), a single-rule shorthand for --proxy
     #[arg(long)]
     proxy_base: Option<String>,
 
@@ -72,6 +226,14 @@ struct Cli {
     #[arg(long)]
     proxy_target: Option<String>,
 
+    /// Log proxied requests and responses (headers, first 16 KB of bodies) as proxy_capture events
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, require_equals = true, default_missing_value = "true", value_parser = BoolishValueParser::new(), hide_possible_values = true)]
+    proxy_capture: Option<bool>,
+
+    /// Extra header to redact in proxy captures (can be repeated; Authorization and cookies always are)
+    #[arg(long = "proxy-capture-redact", value_name = "HEADER")]
+    proxy_capture_redact: Vec<String>,
+
     /// Custom response header (can be repeated, format: "Key: Value")
     #[arg(long = "header")]
     headers: Vec<String>,
@@ -80,9 +242,37 @@ struct Cli {
     #[arg(long = "mount")]
     mounts: Vec<String>,
 
-    /// Enable HTTPS (auto-generates self-signed cert if --cert/--key not provided)
-    #[arg(long, default_value_t = false)]
-    https: bool,
+    /// Enable HTTPS (auto-generates a dev cert signed by the local CA if --cert/--key not provided)
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, require_equals = true, default_missing_value = "true", value_parser = BoolishValueParser::new(), hide_possible_values = true)]
+    https: Option<bool>,
+
+    /// Extra hostname for the generated cert (can be repeated, wildcards allowed, e.g. "*.myapp.test")
+    #[arg(long = "cert-host")]
+    cert_hosts: Vec<String>,
+
+    /// Require client certificates signed by this CA (PEM) on the HTTPS listener (mTLS)
+    #[arg(long)]
+    client_ca: Option<String>,
+
+    /// Also serve plain HTTP on this port while HTTPS runs on --port
+    #[arg(long)]
+    http_port: Option<u16>,
+
+    /// Redirect the plain-HTTP port to HTTPS (live reload and the proxy stay on HTTP)
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, require_equals = true, default_missing_value = "true", value_parser = BoolishValueParser::new(), hide_possible_values = true)]
+    redirect_http: Option<bool>,
+
+    /// Accept HTTP/2 without TLS (prior knowledge) on plain-HTTP ports
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, require_equals = true, default_missing_value = "true", value_parser = BoolishValueParser::new(), hide_possible_values = true)]
+    h2c: Option<bool>,
+
+    /// Compress responses (br, zstd, gzip) and serve precompressed siblings like app.js.br
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, require_equals = true, default_missing_value = "true", value_parser = BoolishValueParser::new(), hide_possible_values = true)]
+    compress: Option<bool>,
+
+    /// List the contents of folders without index.html (HTML, or JSON for Accept: application/json)
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, require_equals = true, default_missing_value = "true", value_parser = BoolishValueParser::new(), hide_possible_values = true)]
+    dir_listing: Option<bool>,
 
     /// File extensions to watch for live reload (can be repeated, e.g. --watch-ext html --watch-ext css)
     /// Defaults to UI-related extensions (html, css, js, ts, etc.). Use "*" to watch all files.
@@ -90,17 +280,21 @@ struct Cli {
     watch_extensions: Vec<String>,
 
     /// Disable event logging (no .hotplate/logs/events-*.jsonl files)
-    #[arg(long, default_value_t = false)]
-    no_event_log: bool,
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, require_equals = true, default_missing_value = "true", value_parser = BoolishValueParser::new(), hide_possible_values = true)]
+    no_event_log: Option<bool>,
 
-    /// Run as MCP server (stdio JSON-RPC) instead of HTTP server
-    #[arg(long, default_value_t = false)]
+    /// Deprecated alias for `hotplate mcp`
+    #[arg(long, default_value_t = false, hide = true)]
     mcp: bool,
+
+    /// Print the resolved configuration (with the source of each value) as JSON and exit
+    #[arg(long, default_value_t = false)]
+    print_config: bool,
 }
 
 // ───────────────────── Config ─────────────────────
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Config {
     pub host: String,
     pub port: u16,
@@ -111,28 +305,78 @@ pub struct Config {
     pub full_reload: bool,
     pub workspace: PathBuf,
     pub ignore_patterns: Vec<String>,
+    /// Extra globs never served (on top of `access::DEFAULT_DENY`).
+    pub deny_patterns: Vec<String>,
+    pub allow_dotfiles: bool,
+    /// Client CIDRs allowed besides loopback (empty = everyone).
+    pub allow_ips: Vec<String>,
+    pub loopback_only: bool,
+    /// Required Basic auth credentials, `user:pass` (never printed).
+    #[serde(skip_serializing)]
+    pub basic_auth: Option<String>,
     pub watch_extensions: Vec<String>,
+    /// Extra DNS names (and wildcards) for the generated dev cert.
+    pub cert_hosts: Vec<String>,
+    /// CA bundle that client certs must chain to (mutual TLS).
+    pub client_ca: Option<PathBuf>,
+    /// Extra plain-HTTP port served next to HTTPS (dual-listener mode).
+    pub http_port: Option<u16>,
+    /// Redirect requests on `http_port` to HTTPS.
+    pub redirect_http: bool,
+    /// Accept cleartext HTTP/2 on plain-HTTP listeners (HTTPS always offers h2).
+    pub h2c: bool,
+    /// Compress responses and serve precompressed `.br` / `.zst` / `.gz` files.
+    pub compress: bool,
+    /// Directory index pages for folders without index.html.
+    pub dir_listing: bool,
     pub spa_file: Option<String>,
-    pub proxy_base: Option<String>,
-    pub proxy_target: Option<String>,
+    /// Validated proxy rules, including the `proxy_base`/`proxy_target` shorthand.
+    pub proxies: Vec<ProxyRule>,
+    /// Record proxied exchanges as `proxy_capture` events.
+    pub proxy_capture: bool,
+    /// Header names redacted in captures, on top of Authorization and cookies.
+    pub proxy_capture_redact: Vec<String>,
     pub headers: Vec<(String, String)>,
     pub mounts: Vec<(String, PathBuf)>,
     pub event_log: bool,
+    /// Passphrase for an encrypted private key (never printed).
+    #[serde(skip_serializing)]
+    pub key_passphrase: Option<String>,
+    /// Project config file the values were loaded from, if any.
+    pub config_file: Option<PathBuf>,
+    /// Active profile from the project config, if any.
+    pub profile: Option<String>,
+    /// Which layer each value was resolved from (see `--print-config`).
+    pub sources: BTreeMap<&'static str, ConfigSource>,
 }
 
 // ───────────────────── VS Code settings.json ─────────────────────
 
 #[derive(Debug, Deserialize, Default)]
-#[allow(dead_code)]
 struct VsCodeHttps {
     enable: Option<bool>,
     cert: Option<String>,
     key: Option<String>,
     passphrase: Option<String>,
+    hosts: Option<Vec<String>>,
+    #[serde(rename = "clientCa")]
+    client_ca: Option<String>,
+    #[serde(rename = "httpPort")]
+    http_port: Option<u16>,
+    #[serde(rename = "redirectHttp")]
+    redirect_http: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct VsCodeProxy {
+    enable: Option<bool>,
+    #[serde(rename = "baseUri")]
+    base_uri: Option<String>,
+    #[serde(rename = "proxyUri")]
+    proxy_uri: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Default)]
-#[allow(dead_code)]
 struct VsCodeSettings {
     #[serde(rename = "hotplate.host")]
     host: Option<String>,
@@ -142,8 +386,106 @@ struct VsCodeSettings {
     root: Option<String>,
     #[serde(rename = "hotplate.https")]
     https: Option<VsCodeHttps>,
+    // Dotted form, as contributed by the extension ("hotplate.https.enable": true)
+    #[serde(rename = "hotplate.https.enable")]
+    https_enable: Option<bool>,
+    #[serde(rename = "hotplate.https.cert")]
+    https_cert: Option<String>,
+    #[serde(rename = "hotplate.https.key")]
+    https_key: Option<String>,
+    #[serde(rename = "hotplate.https.passphrase")]
+    https_passphrase: Option<String>,
+    #[serde(rename = "hotplate.https.hosts")]
+    https_hosts: Option<Vec<String>>,
+    #[serde(rename = "hotplate.https.clientCa")]
+    https_client_ca: Option<String>,
+    #[serde(rename = "hotplate.https.httpPort")]
+    https_http_port: Option<u16>,
+    #[serde(rename = "hotplate.https.redirectHttp")]
+    https_redirect_http: Option<bool>,
+    #[serde(rename = "hotplate.liveReload")]
+    live_reload: Option<bool>,
+    #[serde(rename = "hotplate.fullReload")]
+    full_reload: Option<bool>,
+    #[serde(rename = "hotplate.ignoreFiles")]
+    ignore_files: Option<Vec<String>>,
+    #[serde(rename = "hotplate.deny")]
+    deny: Option<Vec<String>>,
+    #[serde(rename = "hotplate.allowDotfiles")]
+    allow_dotfiles: Option<bool>,
+    #[serde(rename = "hotplate.allowIps")]
+    allow_ips: Option<Vec<String>>,
+    #[serde(rename = "hotplate.loopbackOnly")]
+    loopback_only: Option<bool>,
+    #[serde(rename = "hotplate.file")]
+    file: Option<String>,
+    #[serde(rename = "hotplate.proxy")]
+    proxy: Option<VsCodeProxy>,
+    #[serde(rename = "hotplate.proxies")]
+    proxies: Option<Vec<ProxyRule>>,
+    #[serde(rename = "hotplate.proxyCapture")]
+    proxy_capture: Option<bool>,
+    #[serde(rename = "hotplate.proxyCaptureRedact")]
+    proxy_capture_redact: Option<Vec<String>>,
+    #[serde(rename = "hotplate.headers", default, deserialize_with = "project::header_list")]
+    headers: Option<Vec<(String, String)>>,
+    /// `[["/url", "./path"], ...]` — malformed entries are skipped.
+    #[serde(rename = "hotplate.mount")]
+    mount: Option<Vec<Vec<String>>>,
     #[serde(rename = "hotplate.watchExtensions", default)]
     watch_extensions: Option<Vec<String>>,
+    #[serde(rename = "hotplate.h2c")]
+    h2c: Option<bool>,
+    #[serde(rename = "hotplate.compress")]
+    compress: Option<bool>,
+    #[serde(rename = "hotplate.dirListing")]
+    dir_listing: Option<bool>,
+}
+
+impl VsCodeSettings {
+    /// HTTPS settings, merging the dotted keys over the `"hotplate.https": {}` object.
+    fn https(&self) -> VsCodeHttps {
+        let obj = self.https.as_ref();
+        VsCodeHttps {
+            enable: self.https_enable.or_else(|| obj.and_then(|h| h.enable)),
+            cert: self.https_cert.clone().or_else(|| obj.and_then(|h| h.cert.clone())),
+            key: self.https_key.clone().or_else(|| obj.and_then(|h| h.key.clone())),
+            passphrase: self
+                .https_passphrase
+                .clone()
+                .or_else(|| obj.and_then(|h| h.passphrase.clone())),
+            hosts: self.https_hosts.clone().or_else(|| obj.and_then(|h| h.hosts.clone())),
+            client_ca: self.https_client_ca.clone().or_else(|| obj.and_then(|h| h.client_ca.clone())),
+            http_port: self.https_http_port.or_else(|| obj.and_then(|h| h.http_port)),
+            redirect_http: self.https_redirect_http.or_else(|| obj.and_then(|h| h.redirect_http)),
+        }
+    }
+
+    /// Proxy (base, target) — only when `enable` is set, like the extension.
+    fn proxy(&self) -> Option<(String, String)> {
+        let p = self.proxy.as_ref()?;
+        if !p.enable.unwrap_or(false) {
+            return None;
+        }
+        Some((p.base_uri.clone()?, p.proxy_uri.clone()?))
+    }
+
+    /// Mount pairs as (url_path, fs_path).
+    fn mounts(&self) -> Option<Vec<(String, String)>> {
+        let entries = self.mount.as_ref()?;
+        Some(
+            entries
+                .iter()
+                .filter_map(|e| match e.as_slice() {
+                    [url, fs] => Some((url.clone(), fs.clone())),
+                    _ => {
+                        eprintln!("  ⚠ Invalid hotplate.mount entry: {:?}", e);
+                        None
+                    }
+                })
+                .collect(),
+        )
+    }
 }
 
 /// Strip // and /* */ comments and trailing commas from JSONC
@@ -230,17 +572,26 @@ fn strip_jsonc(input: &str) -> String {
 
 fn load_vscode_settings(workspace: &Path) -> Option<VsCodeSettings> {
     let path = workspace.join(".vscode/settings.json");
-    let content = std::fs::read_to_string(&path).ok()?;
-    let cleaned = strip_jsonc(&content);
-    match serde_json::from_str(&cleaned) {
-        Ok(s) => Some(s),
+    match parse_vscode_settings(&path) {
+        Ok(settings) => settings,
         Err(e) => {
-            eprintln!("  ⚠ Failed to parse {}: {}", path.display(), e);
+            eprintln!("  ⚠ {:#}", e);
             None
         }
     }
 }
 
+/// Parse a settings.json file (JSONC). `Ok(None)` if the file doesn't exist.
+fn parse_vscode_settings(path: &Path) -> Result<Option<VsCodeSettings>> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Ok(None);
+    };
+    let cleaned = strip_jsonc(&content);
+    serde_json::from_str(&cleaned)
+        .map(Some)
+        .with_context(|| format!("Failed to parse {}", path.display()))
+}
+
 /// Resolve a possibly-relative path against the workspace root.
 fn resolve_path(workspace: &Path, p: &str) -> PathBuf {
     let path = PathBuf::from(p);
@@ -251,149 +602,106 @@ fn resolve_path(workspace: &Path, p: &str) -> PathBuf {
     }
 }
 
-/// Generate a self-signed TLS certificate and key in `<workspace>/.hotplate/certs/`.
-/// Returns the paths to the generated cert and key files.
-/// If the files already exist, they are reused without regeneration.
-/// Also migrates old `.cert/` directory layout if found.
-pub(crate) fn generate_self_signed_cert(workspace: &Path) -> Result<(PathBuf, PathBuf)> {
-    let cert_dir = workspace.join(".hotplate").join("certs");
-    let cert_path = cert_dir.join("hotplate.crt");
-    let key_path = cert_dir.join("hotplate.key");
-
-    // Migrate from old .cert/ layout → .hotplate/certs/
-    let old_cert_dir = workspace.join(".cert");
-    let old_cert = old_cert_dir.join("hotplate.crt");
-    let old_key = old_cert_dir.join("hotplate.key");
-    if old_cert.exists() && old_key.exists() && !cert_path.exists() {
-        std::fs::create_dir_all(&cert_dir)
-            .with_context(|| format!("Failed to create directory: {}", cert_dir.display()))?;
-        std::fs::rename(&old_cert, &cert_path).ok();
-        std::fs::rename(&old_key, &key_path).ok();
-        // Remove old directory if empty
-        let _ = std::fs::remove_dir(&old_cert_dir);
-        println!("  🔄 Migrated certs from .cert/ → .hotplate/certs/");
-    }
-
-    // Reuse existing certs if they exist
-    if cert_path.exists() && key_path.exists() {
-        println!("  🔒 Reusing existing self-signed cert at .hotplate/certs/");
-        return Ok((cert_path, key_path));
-    }
-
-    // Create .hotplate/certs directory
-    std::fs::create_dir_all(&cert_dir)
-        .with_context(|| format!("Failed to create directory: {}", cert_dir.display()))?;
-
-    // Generate self-signed certificate with rcgen
-    let mut params = rcgen::CertificateParams::new(vec![
-        "localhost".to_string(),
-    ])?;
-    params.distinguished_name.push(
-        rcgen::DnType::CommonName,
-        rcgen::DnValue::Utf8String("Hotplate Dev Server".to_string()),
-    );
-    params.distinguished_name.push(
-        rcgen::DnType::OrganizationName,
-        rcgen::DnValue::Utf8String("Hotplate".to_string()),
-    );
-
-    // Add Subject Alternative Names for common dev scenarios
-    params.subject_alt_names = vec![
-        rcgen::SanType::DnsName("localhost".try_into()?),
-        rcgen::SanType::IpAddress(std::net::IpAddr::V4(std::net::Ipv4Addr::new(127, 0, 0, 1))),
-        rcgen::SanType::IpAddress(std::net::IpAddr::V6(std::net::Ipv6Addr::LOCALHOST)),
-    ];
-
-    // Add LAN IPs to SAN so mobile devices can connect without warnings
-    if let Some(lan_ip) = get_lan_ip() {
-        params.subject_alt_names.push(rcgen::SanType::IpAddress(lan_ip));
-    }
-
-    let key_pair = rcgen::KeyPair::generate()?;
-    let cert = params.self_signed(&key_pair)?;
-
-    // Write PEM files
-    std::fs::write(&cert_path, cert.pem())
-        .with_context(|| format!("Failed to write cert: {}", cert_path.display()))?;
-    std::fs::write(&key_path, key_pair.serialize_pem())
-        .with_context(|| format!("Failed to write key: {}", key_path.display()))?;
-
-    println!("  🔒 Generated self-signed certificate in .hotplate/certs/:");
-    println!("     📄 {}", cert_path.display());
-    println!("     🔑 {}", key_path.display());
-
-    Ok((cert_path, key_path))
+/// Resolve the config for serving (generates the dev cert when needed).
+pub(crate) fn build_config(cli: ServeArgs) -> Result<Config> {
+    resolve_config(cli, false)
 }
 
-/// Detect LAN IPv4 address (same logic used in server.rs banner)
-fn get_lan_ip() -> Option<std::net::IpAddr> {
-    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
-    socket.connect("8.8.8.8:80").ok()?;
-    socket.local_addr().ok().map(|a| a.ip())
+/// Resolve the config for a live reload. The running cert/key are kept, so the
+/// dev cert is never regenerated here.
+pub(crate) fn reload_config(cli: ServeArgs) -> Result<Config> {
+    resolve_config(cli, true)
 }
 
-fn build_config(cli: Cli) -> Result<Config> {
+/// Resolve the config from all layers. With `dry_run`, nothing is written to
+/// disk (no dev cert generation) — used by `--print-config` and `doctor`.
+pub(crate) fn resolve_config(cli: ServeArgs, dry_run: bool) -> Result<Config> {
+    let env = env::from_env()?;
+
     let workspace = cli
         .workspace
+        .clone()
+        .or(env.workspace.clone())
         .map(PathBuf::from)
         .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
 
-    let vs = load_vscode_settings(&workspace);
-
-    // Merge: CLI > .vscode/settings.json > defaults
-    let host = cli.host;
-    let port = cli.port;
-    let live_reload = !cli.no_reload;
-    let full_reload = cli.full_reload;
+    // Project config: --config > hotplate.toml > .hotplate/config.toml
+    let config_file = match cli.config.clone().or(env.config.clone()) {
+        Some(c) => {
+            let p = resolve_path(&workspace, &c);
+            anyhow::ensure!(p.is_file(), "Config file not found: {}", p.display());
+            Some(p)
+        }
+        None => project::find_project_config(&workspace),
+    };
+    let mut file = match config_file {
+        Some(ref p) => project::load_project_config(p)?,
+        None => ProjectConfig::default(),
+    };
 
-    // Root: CLI --root > vscode root > "."
-    let root_str = cli.root.or_else(|| {
-        vs.as_ref()
-            .and_then(|s| s.root.as_ref())
-            .map(|r| r.trim_start_matches('/').to_string())
-    });
-    let root = match root_str {
-        Some(r) => resolve_path(&workspace, &r),
-        None => workspace.clone(),
+    // Profile: [profiles.<name>] layered above the file's base settings
+    let profile = cli.profile.clone().or(env.profile.clone());
+    let profile_layer = match profile {
+        Some(ref name) => {
+            anyhow::ensure!(
+                config_file.is_some(),
+                "--profile '{}' requires a project config file (hotplate.toml)",
+                name
+            );
+            file.take_profile(name)?
+        }
+        None => ProjectConfig::default(),
     };
 
-    // HTTPS: CLI --cert/--key > vscode https > --https (auto-generate)
-    let https_flag = cli.https;
-    let (cert, key) = match (cli.cert, cli.key) {
-        (Some(c), Some(k)) => (
-            Some(resolve_path(&workspace, &c)),
-            Some(resolve_path(&workspace, &k)),
-        ),
-        _ => {
-            if let Some(https) = vs.as_ref().and_then(|s| s.https.as_ref()) {
-                if https.enable.unwrap_or(false) {
-                    let c = https
-                        .cert
-                        .as_ref()
-                        .map(|p| resolve_path(&workspace, p));
-                    let k = https
-                        .key
-                        .as_ref()
-                        .map(|p| resolve_path(&workspace, p));
-                    (c, k)
-                } else if https_flag {
-                    // --https flag without cert paths in settings → auto-generate
-                    (None, None)
-                } else {
-                    (None, None)
-                }
-            } else {
-                (None, None)
-            }
+    let vs = load_vscode_settings(&workspace).unwrap_or_default();
+
+    // Merge: CLI > HOTPLATE_* env > profile > hotplate.toml > .vscode/settings.json > defaults
+    let mut layers = Layers::new(vec![
+        (ConfigSource::Cli, cli_layer(&cli)),
+        (ConfigSource::Env, cli_layer(&env)),
+        (ConfigSource::Profile, profile_layer),
+        (ConfigSource::ConfigFile, file),
+        (ConfigSource::VsCode, vs_layer(&vs)),
+    ]);
+
+    let host = layers
+        .get("host", |l| l.host.clone())
+        .unwrap_or_else(|| "0.0.0.0".to_string());
+    let port = layers.get("port", |l| l.port).unwrap_or(5500);
+    let live_reload = layers.get("live_reload", |l| l.live_reload).unwrap_or(true);
+    let full_reload = layers.get("full_reload", |l| l.full_reload).unwrap_or(false);
+    let event_log = layers.get("event_log", |l| l.event_log).unwrap_or(true);
+
+    // Passphrase for an encrypted key: HOTPLATE_KEY_PASSPHRASE > settings.json, else prompted
+    // for at startup. Deliberately not a CLI flag (visible in `ps`) nor a hotplate.toml key (committed).
+    let key_passphrase = match env::var("KEY_PASSPHRASE") {
+        Some(p) => {
+            layers.set_source("key_passphrase", ConfigSource::Env);
+            Some(p)
         }
+        None => vs.https().passphrase.inspect(|_| {
+            layers.set_source("key_passphrase", ConfigSource::VsCode);
+        }),
     };
 
-    // Auto-generate self-signed cert when --https is used but no cert/key provided
-    let (cert, key) = if cert.is_none() && key.is_none() && https_flag {
-        let (c, k) = generate_self_signed_cert(&workspace)?;
-        (Some(c), Some(k))
-    } else {
-        (cert, key)
+    // Root: defaults to the workspace itself
+    let root = match layers.get("root", |l| l.root.clone()) {
+        Some(r) => resolve_path(&workspace, &r),
+        None => workspace.clone(),
+    };
+
+    // HTTPS: explicit cert/key > https flag (auto-generate)
+    // cert and key are one setting: both come from the first layer that sets either
+    let https = layers.get("https", |l| l.https).unwrap_or(false);
+    let pair = layers.get("cert", |l| {
+        (l.cert.is_some() || l.key.is_some()).then(|| (l.cert.clone(), l.key.clone()))
+    });
+    layers.set_source("key", layers.source("cert"));
+    let (cert, key) = match pair {
+        Some((Some(c), Some(k))) => (Some(resolve_path(&workspace, &c)), Some(resolve_path(&workspace, &k))),
+        Some((Some(c), None)) => anyhow::bail!("cert {} is set without a key (set both in the same place)", c),
+        Some((None, Some(k))) => anyhow::bail!("key {} is set without a cert (set both in the same place)", k),
+        _ => (None, None),
     };
 
     // Validate cert/key files exist
@@ -405,17 +713,129 @@ fn build_config(cli: Cli) -> Result<Config> {
     }
     anyhow::ensure!(root.exists(), "Root directory not found: {}", root.display());
 
-    let mounts = parse_mounts(&cli.mounts, &workspace);
+    let cert_hosts = layers.get("cert_hosts", |l| l.cert_hosts.clone()).unwrap_or_default();
+    for host in &cert_hosts {
+        cert::validate_cert_host(host)?;
+    }
 
-    // Watch extensions: CLI --watch-ext > vscode watchExtensions > [] (use defaults in watcher)
-    let watch_extensions = if !cli.watch_extensions.is_empty() {
-        cli.watch_extensions
+    // Auto-generate a dev cert when --https is used but no cert/key provided.
+    // A dry run only reports the paths, without touching the disk.
+    let (cert, key) = if cert.is_none() && key.is_none() && https {
+        layers.set_source("cert", ConfigSource::Generated);
+        layers.set_source("key", ConfigSource::Generated);
+        let (c, k) = if dry_run {
+            cert::dev_cert_paths(&workspace)
+        } else {
+            cert::generate_dev_cert(&workspace, &cert_hosts)?
+        };
+        (Some(c), Some(k))
     } else {
-        vs.as_ref()
-            .and_then(|s| s.watch_extensions.clone())
-            .unwrap_or_default()
+        (cert, key)
+    };
+
+    let client_ca = match layers.get("client_ca", |l| l.client_ca.clone()) {
+        Some(ca) if cert.is_none() => {
+            eprintln!("  ⚠ client_ca {} ignored: HTTPS is not enabled", ca);
+            None
+        }
+        Some(ca) => {
+            let ca = resolve_path(&workspace, &ca);
+            anyhow::ensure!(ca.exists(), "Client CA not found: {}", ca.display());
+            Some(ca)
+        }
+        None => None,
     };
 
+    // Dual-listener mode only makes sense with HTTPS on the main port
+    let http_port = match layers.get("http_port", |l| l.http_port) {
+        Some(p) if cert.is_none() => {
+            eprintln!("  ⚠ http_port {} ignored: HTTPS is not enabled", p);
+            None
+        }
+        Some(p) => {
+            anyhow::ensure!(p != port, "http_port must differ from port ({})", port);
+            // Even with redirect_http, /__lr and the proxy stay reachable over plain HTTP
+            anyhow::ensure!(
+                client_ca.is_none(),
+                "http_port can't be combined with client_ca: the plain-HTTP listener would serve without a client cert"
+            );
+            Some(p)
+        }
+        None => None,
+    };
+    let redirect_http = layers.get("redirect_http", |l| l.redirect_http).unwrap_or(false);
+    let h2c = layers.get("h2c", |l| l.h2c).unwrap_or(false);
+    let compress = layers.get("compress", |l| l.compress).unwrap_or(false);
+    let dir_listing = layers.get("dir_listing", |l| l.dir_listing).unwrap_or(false);
+
+    let mounts: Vec<(String, PathBuf)> = layers
+        .get("mounts", |l| l.mounts.clone())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|(url_path, fs_path)| resolve_mount(url_path, fs_path, &workspace))
+        .collect();
+    let headers = layers.get("headers", |l| l.headers.clone()).unwrap_or_default();
+    // Empty watch list → watcher uses its built-in defaults
+    let watch_extensions = layers
+        .get("watch_extensions", |l| l.watch_extensions.clone())
+        .unwrap_or_default();
+    let ignore_patterns = layers.get("ignore", |l| l.ignore.clone()).unwrap_or_default();
+    let deny_patterns = layers.get("deny", |l| l.deny.clone()).unwrap_or_default();
+    let allow_dotfiles = layers.get("allow_dotfiles", |l| l.allow_dotfiles).unwrap_or(false);
+    let allow_ips = layers.get("allow_ips", |l| l.allow_ips.clone()).unwrap_or_default();
+    for entry in &allow_ips {
+        access::parse_allowed_ip(entry)?;
+    }
+    let loopback_only = layers.get("loopback_only", |l| l.loopback_only).unwrap_or(false);
+    let basic_auth = layers
+        .get("basic_auth", |l| l.basic_auth.clone())
+        .filter(|a| !a.is_empty());
+    if let Some(ref auth) = basic_auth {
+        anyhow::ensure!(auth.contains(':'), "basic_auth must be \"user:pass\"");
+    }
+
+    // Empty strings clear a value set by a lower layer (e.g. in a profile)
+    let spa_file = layers
+        .get("spa_file", |l| l.spa_file.clone())
+        .filter(|f| !f.is_empty());
+    // The base/target shorthand only counts from the layer that sets the `proxy`
+    // list or above: `--proxy /api=...` replaces settings.json's hotplate.proxy
+    let sets_rules = |l: &ProjectConfig| l.proxy.is_some();
+    let proxy_base = layers
+        .get_down_to("proxy_base", sets_rules, |l| l.proxy_base.clone())
+        .filter(|b| !b.is_empty());
+    let proxy_target = layers
+        .get_down_to("proxy_target", sets_rules, |l| l.proxy_target.clone())
+        .filter(|t| !t.is_empty());
+
+    // Proxy rules, plus the base/target shorthand as one more rule
+    let mut proxies = layers.get("proxy", |l| l.proxy.clone()).unwrap_or_default();
+    if let (Some(base), Some(target)) = (proxy_base, proxy_target) {
+        proxies.push(ProxyRule::new(&base, &target));
+    }
+    let proxies = proxies
+        .into_iter()
+        .map(ProxyRule::validate)
+        .collect::<Result<Vec<_>>>()?;
+    for (i, rule) in proxies.iter().enumerate() {
+        anyhow::ensure!(
+            !proxies[..i].iter().any(|r| r.path == rule.path),
+            "Duplicate proxy rule for {}",
+            rule.path
+        );
+        // Nested either way, one would silently shadow part of the other
+        if let Some((url, _)) = mounts.iter().find(|(url, _)| {
+            let url = url.trim_end_matches('/');
+            rule.matches(url) || rule.path.strip_prefix(url).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        }) {
+            anyhow::bail!("Proxy rule for {} overlaps the mount at {}", rule.path, url);
+        }
+    }
+    let proxy_capture = layers.get("proxy_capture", |l| l.proxy_capture).unwrap_or(false);
+    let proxy_capture_redact = layers
+        .get("proxy_capture_redact", |l| l.proxy_capture_redact.clone())
+        .unwrap_or_default();
+
     Ok(Config {
         host,
         port,
@@ -425,17 +845,117 @@ fn build_config(cli: Cli) -> Result<Config> {
         live_reload,
         full_reload,
         workspace,
-        ignore_patterns: cli.ignore,
+        ignore_patterns,
+        deny_patterns,
+        allow_dotfiles,
+        allow_ips,
+        loopback_only,
+        basic_auth,
         watch_extensions,
-        spa_file: cli.file,
-        proxy_base: cli.proxy_base,
-        proxy_target: cli.proxy_target,
-        headers: parse_headers(&cli.headers),
+        cert_hosts,
+        client_ca,
+        http_port,
+        redirect_http,
+        h2c,
+        compress,
+        dir_listing,
+        spa_file,
+        proxies,
+        proxy_capture,
+        proxy_capture_redact,
+        headers,
         mounts,
-        event_log: !cli.no_event_log,
+        event_log,
+        key_passphrase,
+        config_file,
+        profile,
+        sources: layers.into_sources(),
     })
 }
 
+/// Convert CLI flags (or their `HOTPLATE_*` env equivalents) into a config layer.
+/// Flags that are off mean "not set", so lower layers still apply.
+fn cli_layer(cli: &ServeArgs) -> ProjectConfig {
+    let non_empty = |v: &Vec<String>| (!v.is_empty()).then(|| v.clone());
+    ProjectConfig {
+        host: cli.host.clone(),
+        port: cli.port,
+        root: cli.root.clone(),
+        https: cli.https,
+        cert: cli.cert.clone(),
+        key: cli.key.clone(),
+        live_reload: cli.no_reload.map(|off| !off),
+        full_reload: cli.full_reload,
+        ignore: non_empty(&cli.ignore),
+        deny: non_empty(&cli.deny),
+        allow_dotfiles: cli.allow_dotfiles,
+        allow_ips: non_empty(&cli.allow_ips),
+        loopback_only: cli.loopback_only,
+        basic_auth: cli.basic_auth.clone(),
+        watch_extensions: non_empty(&cli.watch_extensions),
+        cert_hosts: non_empty(&cli.cert_hosts),
+        client_ca: cli.client_ca.clone(),
+        http_port: cli.http_port,
+        redirect_http: cli.redirect_http,
+        h2c: cli.h2c,
+        compress: cli.compress,
+        dir_listing: cli.dir_listing,
+        spa_file: cli.file.clone(),
+        proxy: (!cli.proxies.is_empty()).then(|| cli.proxies.clone()),
+        proxy_base: cli.proxy_base.clone(),
+        proxy_target: cli.proxy_target.clone(),
+        proxy_capture: cli.proxy_capture,
+        proxy_capture_redact: non_empty(&cli.proxy_capture_redact),
+        headers: (!cli.headers.is_empty()).then(|| parse_headers(&cli.headers)),
+        mounts: (!cli.mounts.is_empty()).then(|| parse_mounts(&cli.mounts).into_iter().collect()),
+        event_log: cli.no_event_log.map(|off| !off),
+        profiles: Default::default(),
+    }
+}
+
+/// Convert `.vscode/settings.json` into a config layer.
+fn vs_layer(vs: &VsCodeSettings) -> ProjectConfig {
+    let https = vs.https();
+    let https_enabled = https.enable.unwrap_or(false);
+    let proxy = vs.proxy();
+    ProjectConfig {
+        host: vs.host.clone(),
+        port: vs.port,
+        root: vs.root.as_ref().map(|r| r.trim_start_matches('/').to_string()),
+        https: https.enable,
+        // Cert paths only apply when HTTPS is enabled in settings
+        cert: https.cert.filter(|_| https_enabled),
+        key: https.key.filter(|_| https_enabled),
+        live_reload: vs.live_reload,
+        full_reload: vs.full_reload,
+        ignore: vs.ignore_files.clone(),
+        deny: vs.deny.clone(),
+        allow_dotfiles: vs.allow_dotfiles,
+        allow_ips: vs.allow_ips.clone(),
+        loopback_only: vs.loopback_only,
+        // Credentials don't belong in a committed settings.json
+        basic_auth: None,
+        watch_extensions: vs.watch_extensions.clone(),
+        cert_hosts: https.hosts,
+        client_ca: https.client_ca.filter(|_| https_enabled),
+        http_port: https.http_port,
+        redirect_http: https.redirect_http,
+        h2c: vs.h2c,
+        compress: vs.compress,
+        dir_listing: vs.dir_listing,
+        spa_file: vs.file.clone(),
+        proxy: vs.proxies.clone(),
+        proxy_base: proxy.as_ref().map(|(base, _)| base.clone()),
+        proxy_target: proxy.map(|(_, target)| target),
+        proxy_capture: vs.proxy_capture,
+        proxy_capture_redact: vs.proxy_capture_redact.clone(),
+        headers: vs.headers.clone(),
+        mounts: vs.mounts().map(|m| m.into_iter().collect()),
+        event_log: None,
+        profiles: Default::default(),
+    }
+}
+
 /// Parse "Key: Value" strings into (key, value) tuples.
 fn parse_headers(raw: &[String]) -> Vec<(String, String)> {
     raw.iter()
@@ -452,33 +972,42 @@ fn parse_headers(raw: &[String]) -> Vec<(String, String)> {
         .collect()
 }
 
-/// Parse "/url_path:./fs_path" strings into (url_path, resolved_fs_path) tuples.
-fn parse_mounts(raw: &[String], workspace: &Path) -> Vec<(String, PathBuf)> {
+/// Split "/url_path:./fs_path" strings into (url_path, fs_path) pairs.
+fn parse_mounts(raw: &[String]) -> Vec<(String, String)> {
     raw.iter()
         .filter_map(|m| {
-            let mut parts = m.splitn(2, ':');
-            let url_path = parts.next()?.trim().to_string();
-            let fs_path_str = parts.next()?.trim().to_string();
-            if url_path.is_empty() || fs_path_str.is_empty() {
+            let Some((url_path, fs_path)) = m.split_once(':') else {
                 eprintln!("  ⚠ Invalid mount: {}", m);
                 return None;
-            }
-            // Ensure url_path starts with /
-            let url_path = if url_path.starts_with('/') {
-                url_path
-            } else {
-                format!("/{}", url_path)
             };
-            let fs_path = resolve_path(workspace, &fs_path_str);
-            if !fs_path.exists() {
-                eprintln!("  ⚠ Mount path does not exist: {} → {}", url_path, fs_path.display());
-                // Still allow it — directory might be created later
-            }
-            Some((url_path, fs_path))
+            Some((url_path.to_string(), fs_path.to_string()))
         })
         .collect()
 }
 
+/// Normalize a single mount: ensure the URL path starts with `/` and resolve
+/// the filesystem path against the workspace.
+fn resolve_mount(url_path: &str, fs_path_str: &str, workspace: &Path) -> Option<(String, PathBuf)> {
+    let url_path = url_path.trim();
+    let fs_path_str = fs_path_str.trim();
+    if url_path.is_empty() || fs_path_str.is_empty() {
+        eprintln!("  ⚠ Invalid mount: {}:{}", url_path, fs_path_str);
+        return None;
+    }
+    // Ensure url_path starts with /
+    let url_path = if url_path.starts_with('/') {
+        url_path.to_string()
+    } else {
+        format!("/{}", url_path)
+    };
+    let fs_path = resolve_path(workspace, fs_path_str);
+    if !fs_path.exists() {
+        eprintln!("  ⚠ Mount path does not exist: {} → {}", url_path, fs_path.display());
+        // Still allow it — directory might be created later
+    }
+    Some((url_path, fs_path))
+}
+
 // ───────────────────── Main ─────────────────────
 
 fn main() -> Result<()> {
@@ -489,13 +1018,114 @@ fn main() -> Result<()> {
 
     let cli = Cli::parse();
 
-    // ── MCP mode: stdio JSON-RPC server for AI agents ──
-    if cli.mcp {
-        return mcp::run_mcp().map_err(|e| anyhow::anyhow!("{}", e));
+    // Bare `hotplate [OPTIONS]` keeps meaning `serve`
+    match cli.command.unwrap_or(Command::Serve(cli.serve)) {
+        Command::Serve(args) => serve(args),
+        Command::Mcp => run_mcp(),
+        Command::Cert { action, workspace, cert_hosts } => {
+            run_cert(action, &workspace_dir(workspace), cert_hosts)
+        }
+        Command::Logs(args) => run_logs(args),
+        Command::Doctor(args) => doctor::run(args),
+    }
+}
+
+/// Workspace from `--workspace`, `HOTPLATE_WORKSPACE`, or the current directory.
+fn workspace_dir(arg: Option<String>) -> PathBuf {
+    arg.or_else(|| env::var("WORKSPACE"))
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")))
+}
+
+fn run_mcp() -> Result<()> {
+    mcp::run_mcp().map_err(|e| anyhow::anyhow!("{}", e))
+}
+
+fn serve(args: ServeArgs) -> Result<()> {
+    // Legacy `--mcp` flag
+    if args.mcp || env::flag("MCP")?.unwrap_or(false) {
+        return run_mcp();
+    }
+
+    // ── Print resolved config as JSON and exit ──
+    if args.print_config || env::flag("PRINT_CONFIG")?.unwrap_or(false) {
+        let config = resolve_config(args, true).context("Failed to load configuration")?;
+        println!("{}", serde_json::to_string_pretty(&config)?);
+        return Ok(());
     }
 
     // ── Normal mode: HTTP/HTTPS dev server ──
-    let config = build_config(cli).context("Failed to load configuration")?;
+    let config = build_config(args.clone()).context("Failed to load configuration")?;
+    let reloader: server::ConfigReloader = std::sync::Arc::new(move || reload_config(args.clone()));
     let rt = tokio::runtime::Runtime::new().context("Failed to create tokio runtime")?;
-    rt.block_on(server::run(config, None))
+    rt.block_on(server::run(config, None, Some(reloader)))
+}
+
+fn run_cert(action: CertAction, workspace: &Path, cert_hosts: Vec<String>) -> Result<()> {
+    // Same SANs as `serve` would use: --cert-host > HOTPLATE_CERT_HOST > config files
+    let hosts = || -> Result<Vec<String>> {
+        let args = ServeArgs {
+            workspace: Some(workspace.to_string_lossy().to_string()),
+            cert_hosts: cert_hosts.clone(),
+            ..Default::default()
+        };
+        Ok(resolve_config(args, true)?.cert_hosts)
+    };
+    match action {
+        CertAction::Show => cert::show(workspace),
+        CertAction::Create => cert::generate_dev_cert(workspace, &hosts()?).map(|_| ()),
+        CertAction::Regenerate => cert::regenerate(workspace, &hosts()?).map(|_| ()),
+        CertAction::ExportCa { out } => cert::export_ca(workspace, &out),
+    }
+}
+
+fn run_logs(args: LogsArgs) -> Result<()> {
+    let workspace = workspace_dir(args.workspace);
+    let sessions = events::session_files(&workspace);
+    anyhow::ensure!(
+        !sessions.is_empty(),
+        "No event logs found in {}",
+        workspace.join(".hotplate").join("logs").display()
+    );
+
+    if args.list {
+        for path in &sessions {
+            let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            println!("  {} ({}B)", path.file_name().unwrap_or_default().to_string_lossy(), size);
+        }
+        return Ok(());
+    }
+
+    let file = match args.session {
+        Some(ref id) => sessions
+            .iter()
+            .find(|p| p.file_name().is_some_and(|n| n.to_string_lossy().contains(id.as_str())))
+            .cloned()
+            .with_context(|| format!("Session not found: {}", id))?,
+        None => sessions.last().cloned().unwrap_or_default(),
+    };
+
+    let (total, entries) = events::query_events(&file, args.kind.as_deref(), args.limit)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+
+    if args.json {
+        for entry in &entries {
+            println!("{}", entry);
+        }
+        return Ok(());
+    }
+
+    println!(
+        "  📋 {} — showing {} of {} events",
+        file.file_name().unwrap_or_default().to_string_lossy(),
+        entries.len(),
+        total
+    );
+    for entry in &entries {
+        let ts = entry.get("ts").and_then(|v| v.as_str()).unwrap_or("");
+        let kind = entry.get("kind").and_then(|v| v.as_str()).unwrap_or("?");
+        let data = entry.get("data").map(|d| d.to_string()).unwrap_or_default();
+        println!("  {}  {:<15} {}", ts, kind, data);
+    }
+    Ok(())
 }
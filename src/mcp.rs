@@ -7,7 +7,7 @@
 //!   - `hotplate_reload`  — force reload all connected browsers
 //!
 //! Usage:
-//!   hotplate --mcp   # runs MCP stdio server instead of HTTP server
+//!   hotplate mcp     # runs MCP stdio server instead of HTTP server (`--mcp` still works)
 //!
 //! Architecture (following memory-graph pattern):
 //!   AI Agent ← JSON-RPC 2.0 (stdin/stdout) → McpServer → tools → HotplateState
@@ -15,6 +15,7 @@
 //! Future: SSE transport can be added alongside stdio.
 
 use crate::jsonrpc::{JsonRpcError, JsonRpcRequest, JsonRpcResponse};
+use crate::proxy::ProxyRule;
 use crate::server::ExternalChannels;
 use crate::Config;
 
@@ -49,6 +50,9 @@ pub trait Tool: Send + Sync {
 
 // ───────────────────── Shared state ─────────────────────
 
+/// Receiver for browser → MCP responses (id, payload), shared by tool calls.
+pub type BrowserResponseRx = Arc<tokio::sync::Mutex<tokio::sync::mpsc::UnboundedReceiver<(String, String)>>>;
+
 /// Shared state between MCP tools and the background HTTP server.
 pub struct HotplateState {
     /// Whether the HTTP server is currently running.
@@ -62,15 +66,15 @@ pub struct HotplateState {
     /// Server task handle (so we can abort on `hotplate_stop`).
     pub server_handle: Option<tokio::task::JoinHandle<()>>,
     /// Receiver for screenshot responses from the browser (id, base64).
-    pub screenshot_rx: Option<Arc<tokio::sync::Mutex<tokio::sync::mpsc::UnboundedReceiver<(String, String)>>>>,
+    pub screenshot_rx: Option<BrowserResponseRx>,
     /// Shared in-memory buffer of browser console logs.
     pub console_logs: Option<crate::server::ConsoleLogBuffer>,
     /// Shared in-memory buffer of browser network requests.
     pub network_logs: Option<crate::server::NetworkLogBuffer>,
     /// Receiver for DOM query responses from the browser (id, json_data).
-    pub dom_rx: Option<Arc<tokio::sync::Mutex<tokio::sync::mpsc::UnboundedReceiver<(String, String)>>>>,
+    pub dom_rx: Option<BrowserResponseRx>,
     /// Receiver for eval responses from the browser (id, result_json).
-    pub eval_rx: Option<Arc<tokio::sync::Mutex<tokio::sync::mpsc::UnboundedReceiver<(String, String)>>>>,
+    pub eval_rx: Option<BrowserResponseRx>,
 }
 
 // ───────────────────── McpServer ─────────────────────
@@ -216,6 +220,20 @@ fn text_response(text: String) -> Value {
     })
 }
 
+/// Workspace whose `.hotplate/logs/` the log tools read: the server's, else the cwd.
+fn log_workspace(state: &std::sync::Mutex<HotplateState>) -> McpResult<std::path::PathBuf> {
+    let st = state.lock().map_err(|e| format!("Lock: {e}"))?;
+    Ok(match st.config {
+        Some(ref cfg) => cfg.workspace.clone(),
+        None => std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from(".")),
+    })
+}
+
+/// Most recent session log — a running server always writes to the newest one.
+fn latest_session(workspace: &std::path::Path) -> Option<std::path::PathBuf> {
+    crate::events::session_files(workspace).pop()
+}
+
 // ───────────────────── hotplate_status ─────────────────────
 
 struct StatusTool {
@@ -247,6 +265,7 @@ impl Tool for StatusTool {
                 "root":    cfg.root.display().to_string(),
                 "https":   cfg.cert.is_some(),
                 "live_reload": cfg.live_reload,
+                "profile": cfg.profile,
             })
         } else {
             json!({ "running": false })
@@ -270,9 +289,32 @@ impl Tool for StartTool {
             input_schema: json!({
                 "type": "object",
                 "properties": {
-                    "root":  { "type": "string",  "description": "Root directory (default: .)" },
-                    "port":  { "type": "number",  "description": "Port (default: 5500)" },
-                    "https": { "type": "boolean", "description": "Enable HTTPS (default: false)" }
+                    "root":    { "type": "string",  "description": "Root directory (default: .)" },
+                    "port":    { "type": "number",  "description": "Port (default: 5500)" },
+                    "https":   { "type": "boolean", "description": "Enable HTTPS (default: false)" },
+                    "profile": { "type": "string",  "description": "Named profile from hotplate.toml" },
+                    "proxy_capture": { "type": "boolean", "description": "Record proxied exchanges for hotplate_proxy_captures (default: false)" },
+                    "proxy": {
+                        "type": "array",
+                        "description": "Proxy rules (replace the configured ones). Each is a CLI spec like \"/api=http://127.0.0.1:8000;rewrite=/v2\" or an object.",
+                        "items": {
+                            "oneOf": [
+                                { "type": "string" },
+                                {
+                                    "type": "object",
+                                    "properties": {
+                                        "path":         { "type": "string", "description": "URL prefix, e.g. /api" },
+                                        "target":       { "type": "string", "description": "Backend URL, e.g. http://127.0.0.1:8000" },
+                                        "rewrite":      { "description": "\"strip\" (default), \"keep\", {\"replace\": \"/v2\"} or {\"regex\": [pattern, replacement]}" },
+                                        "changeOrigin": { "type": "boolean", "description": "Send the target's Host (default: true)" },
+                                        "headers":      { "type": "object", "description": "Extra request headers" },
+                                        "timeout":      { "type": "number", "description": "Seconds to wait for response headers" }
+                                    },
+                                    "required": ["path", "target"]
+                                }
+                            ]
+                        }
+                    }
                 },
                 "required": []
             }),
@@ -286,28 +328,27 @@ impl Tool for StartTool {
             return Ok(text_response("Server is already running. Stop it first.".into()));
         }
 
-        let root_str = params.get("root").and_then(|v| v.as_str()).unwrap_or(".");
-        let port  = params.get("port").and_then(|v| v.as_u64()).unwrap_or(5500) as u16;
-        let https = params.get("https").and_then(|v| v.as_bool()).unwrap_or(false);
-
-        let workspace = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
-        let root = if std::path::Path::new(root_str).is_absolute() {
-            std::path::PathBuf::from(root_str)
-        } else {
-            workspace.join(root_str)
+        let proxies = match parse_proxy_rules(params.get("proxy")) {
+            Ok(p) => p,
+            Err(e) => return Ok(text_response(format!("Config error: {e:#}"))),
         };
-        if !root.exists() {
-            return Ok(text_response(format!("Root directory not found: {}", root.display())));
-        }
 
-        let (cert, key) = if https {
-            match crate::generate_self_signed_cert(&workspace) {
-                Ok(pair) => (Some(pair.0), Some(pair.1)),
-                Err(e) => return Ok(text_response(format!("Cert error: {e}"))),
-            }
-        } else {
-            (None, None)
+        // Resolve through the same pipeline as the CLI (hotplate.toml, profiles, settings.json)
+        let cli = crate::ServeArgs {
+            root: params.get("root").and_then(|v| v.as_str()).map(String::from),
+            port: params.get("port").and_then(|v| v.as_u64()).map(|p| p as u16),
+            https: params.get("https").and_then(|v| v.as_bool()),
+            profile: params.get("profile").and_then(|v| v.as_str()).map(String::from),
+            proxies,
+            proxy_capture: params.get("proxy_capture").and_then(|v| v.as_bool()),
+            ..Default::default()
         };
+        let config = match crate::build_config(cli.clone()) {
+            Ok(c) => c,
+            Err(e) => return Ok(text_response(format!("Config error: {e:#}"))),
+        };
+        let reloader: crate::server::ConfigReloader =
+            Arc::new(move || crate::reload_config(cli.clone()));
 
         let (reload_tx, _) = broadcast::channel::<String>(16);
         let (screenshot_tx, screenshot_rx) = tokio::sync::mpsc::unbounded_channel::<(String, String)>();
@@ -331,35 +372,19 @@ impl Tool for StartTool {
             network_logs,
         };
 
-        let config = Config {
-            host: "0.0.0.0".into(),
-            port,
-            root: root.clone(),
-            cert,
-            key,
-            live_reload: true,
-            full_reload: false,
-            workspace,
-            ignore_patterns: vec![],
-            watch_extensions: vec![],
-            spa_file: None,
-            proxy_base: None,
-            proxy_target: None,
-            headers: vec![],
-            mounts: vec![],
-            event_log: true,
-        };
-
         let scheme = if config.cert.is_some() { "https" } else { "http" };
-        let msg = format!("Server starting on {}://localhost:{} serving {}",
-                          scheme, config.port, config.root.display());
+        let mut msg = format!("Server starting on {}://localhost:{} serving {}",
+                              scheme, config.port, config.root.display());
+        if let Some(ref profile) = config.profile {
+            msg.push_str(&format!(" (profile: {profile})"));
+        }
 
         st.config = Some(config.clone());
         let running = st.running.clone();
         running.store(true, Ordering::Relaxed);
 
         let handle = st.rt_handle.spawn(async move {
-            if let Err(e) = crate::server::run(config, Some(ext)).await {
+            if let Err(e) = crate::server::run(config, Some(ext), Some(reloader)).await {
                 eprintln!("[hotplate-mcp] Server error: {e}");
             }
             running.store(false, Ordering::Relaxed);
@@ -370,6 +395,21 @@ impl Tool for StartTool {
     }
 }
 
+/// `proxy` argument of `hotplate_start`: CLI-style specs or rule objects.
+fn parse_proxy_rules(value: Option<&Value>) -> anyhow::Result<Vec<ProxyRule>> {
+    let Some(value) = value else { return Ok(Vec::new()) };
+    let Some(items) = value.as_array() else { anyhow::bail!("proxy must be an array") };
+    items
+        .iter()
+        .map(|item| match item {
+            Value::String(spec) => spec.parse(),
+            _ => serde_json::from_value::<ProxyRule>(item.clone())
+                .map_err(|e| anyhow::anyhow!("Invalid proxy rule {}: {}", item, e))?
+                .validate(),
+        })
+        .collect()
+}
+
 // ───────────────────── hotplate_stop ─────────────────────
 
 struct StopTool {
@@ -627,8 +667,9 @@ impl Tool for ServerLogsTool {
                 "properties": {
                     "kind": {
                         "type": "string",
-                        "enum": ["all", "server_start", "server_stop", "file_change", "reload_trigger",
-                                 "ws_connect", "ws_disconnect", "http_request", "js_error", "console_log", "network_error"],
+                        "enum": ["all", "server_start", "server_stop", "config_reload", "tls_reload", "file_change", "reload_trigger",
+                                 "ws_connect", "ws_disconnect", "proxy_ws_connect", "proxy_ws_disconnect", "proxy_capture", "http_request",
+                                 "access_denied", "js_error", "console_log", "network_error"],
                         "description": "Filter by event kind. Default: 'all'."
                     },
                     "limit": {
@@ -647,44 +688,20 @@ impl Tool for ServerLogsTool {
     }
 
     fn execute(&self, params: Value) -> McpResult<Value> {
-        let st = self.state.lock().map_err(|e| format!("Lock: {e}"))?;
-
-        // Determine workspace path from config or cwd
-        let workspace = if let Some(ref cfg) = st.config {
-            cfg.workspace.clone()
-        } else {
-            std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."))
-        };
-        drop(st);
-
-        let log_dir = workspace.join(".hotplate").join("logs");
-        if !log_dir.exists() {
-            return Ok(text_response("No event logs found. Server may not have been started with event logging enabled.".into()));
-        }
+        let workspace = log_workspace(&self.state)?;
 
         let session_mode = params.get("session")
             .and_then(|v| v.as_str())
             .unwrap_or("current");
 
-        // List available session files
-        let mut session_files: Vec<std::path::PathBuf> = std::fs::read_dir(&log_dir)
-            .into_iter()
-            .flatten()
-            .filter_map(|e| e.ok())
-            .filter(|e| {
-                let name = e.file_name().to_string_lossy().to_string();
-                name.starts_with("events-") && name.ends_with(".jsonl")
-            })
-            .map(|e| e.path())
-            .collect();
-        session_files.sort();
-
-        if session_files.is_empty() {
-            return Ok(text_response("No event log files found.".into()));
-        }
+        // "current" and "latest" both read the most recent file
+        let Some(log_file) = latest_session(&workspace) else {
+            return Ok(text_response("No event logs found. Server may not have been started with event logging enabled.".into()));
+        };
 
         // Handle "all" mode — list available sessions
         if session_mode == "all" {
+            let session_files = crate::events::session_files(&workspace);
             let sessions: Vec<String> = session_files.iter()
                 .map(|p| {
                     let name = p.file_name().unwrap_or_default().to_string_lossy().to_string();
@@ -699,58 +716,15 @@ impl Tool for ServerLogsTool {
             return Ok(text_response(serde_json::to_string_pretty(&result)?));
         }
 
-        // Pick which file to read
-        let log_file = if session_mode == "current" {
-            // Find the current session's file (from running config)
-            let st = self.state.lock().map_err(|e| format!("Lock: {e}"))?;
-            if st.running.load(Ordering::Relaxed) {
-                // Most recent file is likely the current session
-                session_files.last().cloned()
-            } else {
-                // Server not running — show latest
-                session_files.last().cloned()
-            }
-        } else {
-            // "latest"
-            session_files.last().cloned()
-        };
-
-        let log_file = match log_file {
-            Some(f) => f,
-            None => return Ok(text_response("No event log files found.".into())),
-        };
-
-        // Read and parse the JSONL file
-        let content = match std::fs::read_to_string(&log_file) {
-            Ok(c) => c,
-            Err(e) => return Ok(text_response(format!("Failed to read log file: {e}"))),
-        };
-
-        let kind_filter = params.get("kind")
-            .and_then(|v| v.as_str())
-            .unwrap_or("all");
+        let kind_filter = params.get("kind").and_then(|v| v.as_str());
         let limit = params.get("limit")
             .and_then(|v| v.as_u64())
             .unwrap_or(100) as usize;
 
-        // Parse lines as raw JSON values, filter by kind
-        let mut entries: Vec<Value> = content.lines()
-            .filter(|line| !line.trim().is_empty())
-            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
-            .filter(|entry| {
-                if kind_filter == "all" { return true; }
-                entry.get("kind")
-                    .and_then(|k| k.as_str())
-                    .map(|k| k == kind_filter)
-                    .unwrap_or(false)
-            })
-            .collect();
-
-        // Take last N entries (most recent)
-        let total = entries.len();
-        if entries.len() > limit {
-            entries = entries.split_off(entries.len() - limit);
-        }
+        let (total, entries) = match crate::events::query_events(&log_file, kind_filter, limit) {
+            Ok(r) => r,
+            Err(e) => return Ok(text_response(format!("Failed to read log file: {e}"))),
+        };
 
         let session_name = log_file.file_name()
             .unwrap_or_default()
@@ -768,6 +742,86 @@ impl Tool for ServerLogsTool {
     }
 }
 
+// ───────────────────── hotplate_proxy_captures ─────────────────────
+
+struct ProxyCapturesTool {
+    state: Arc<std::sync::Mutex<HotplateState>>,
+}
+
+impl Tool for ProxyCapturesTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "hotplate_proxy_captures".into(),
+            description: "Get captured proxy exchanges (request/response headers and the first 16 KB of each body, credentials redacted). Requires the server to run with proxy capture enabled.".into(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Only exchanges whose browser path contains this substring (e.g. '/api/users')."
+                    },
+                    "method": {
+                        "type": "string",
+                        "description": "Filter by HTTP method (e.g. 'GET', 'POST'). Default: all."
+                    },
+                    "min_status": {
+                        "type": "integer",
+                        "description": "Only exchanges with at least this status (e.g. 400 for failures). Default: all."
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Max number of exchanges to return (most recent). Default: 20."
+                    }
+                },
+                "required": []
+            }),
+        }
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let Some(log_file) = latest_session(&log_workspace(&self.state)?) else {
+            return Ok(text_response("No event logs found.".into()));
+        };
+
+        let path_filter = params.get("path").and_then(|v| v.as_str());
+        let method_filter = params.get("method").and_then(|v| v.as_str());
+        let min_status = params.get("min_status").and_then(|v| v.as_u64()).unwrap_or(0);
+        let limit = params.get("limit").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+
+        let (_, entries) = match crate::events::query_events(&log_file, Some("proxy_capture"), usize::MAX) {
+            Ok(r) => r,
+            Err(e) => return Ok(text_response(format!("Failed to read log file: {e}"))),
+        };
+
+        let mut captures: Vec<Value> = entries.into_iter().filter(|e| {
+            let data = &e["data"];
+            if let Some(p) = path_filter {
+                if !data["path"].as_str().is_some_and(|path| path.contains(p)) { return false; }
+            }
+            if let Some(m) = method_filter {
+                if !data["method"].as_str().is_some_and(|method| method.eq_ignore_ascii_case(m)) { return false; }
+            }
+            data["status"].as_u64().unwrap_or(0) >= min_status
+        }).collect();
+
+        let total = captures.len();
+        if total == 0 {
+            return Ok(text_response("No matching captured exchanges. Proxy capture is opt-in — start the server with proxy_capture: true (or --proxy-capture).".into()));
+        }
+        if captures.len() > limit {
+            captures = captures.split_off(captures.len() - limit);
+        }
+
+        let result = json!({
+            "total": total,
+            "showing": captures.len(),
+            "captures": captures
+        });
+
+        Ok(text_response(serde_json::to_string_pretty(&result)?))
+    }
+}
+
 // ───────────────────── hotplate_inject ─────────────────────
 
 struct InjectTool {
@@ -1179,10 +1233,11 @@ pub fn run_mcp() -> McpResult<()> {
     server.register_tool(Box::new(ConsoleTool    { state: state.clone() }));
     server.register_tool(Box::new(NetworkTool    { state: state.clone() }));
     server.register_tool(Box::new(ServerLogsTool { state: state.clone() }));
+    server.register_tool(Box::new(ProxyCapturesTool { state: state.clone() }));
     server.register_tool(Box::new(DomTool        { state: state.clone() }));
     server.register_tool(Box::new(EvalTool       { state: state.clone() }));
 
-    eprintln!("[hotplate-mcp] ready — 11 tools registered, waiting for JSON-RPC on stdin…");
+    eprintln!("[hotplate-mcp] ready — 12 tools registered, waiting for JSON-RPC on stdin…");
 
     server.run()
 }
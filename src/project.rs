@@ -0,0 +1,245 @@
+//! Project config file — `hotplate.toml` (or `.hotplate/config.toml`).
+//!
+//! Meant to be committed so the whole team runs the same server without long
+//! shell aliases. Every key is optional and mirrors a field of [`crate::Config`]:
+//!
+//! ```toml
+//! host = "0.0.0.0"
+//! port = 5500
+//! root = "apps"
+//! https = true                    # auto-generate a dev cert (local CA)
+//! # cert = ".hotplate/certs/server.crt"
+//! # key  = ".hotplate/certs/server.key"
+//! cert_hosts = ["myapp.test", "*.myapp.test"]   # extra SANs for the generated cert
+//! # client_ca = "certs/staging-ca.pem"   # require client certs (mTLS)
+//! http_port = 5580                # also serve plain HTTP next to HTTPS
+//! redirect_http = true            # ...and redirect it to HTTPS
+//! h2c = false                     # HTTP/2 without TLS on plain-HTTP ports
+//! compress = true                 # gzip/br/zstd responses, serve app.js.br etc.
+//! dir_listing = true              # index pages for folders without index.html
+//! live_reload = true
+//! full_reload = false
+//! ignore = ["**/*.scss", ".vscode/**"]
+//! deny = ["**/*.sqlite", "drafts/**"]   # never served (adds to the built-in key globs)
+//! allow_dotfiles = false          # `.hotplate/` stays denied either way
+//! allow_ips = ["192.168.1.0/24"]  # clients besides loopback (default: everyone)
+//! loopback_only = false           # only this machine, even on 0.0.0.0
+//! # basic_auth = "dev:secret"     # or HOTPLATE_BASIC_AUTH
+//! watch_extensions = ["html", "css", "js"]
+//! spa_file = "index.html"
+//! proxy_base = "/api"             # shorthand for one [[proxy]] rule
+//! proxy_target = "http://127.0.0.1:8000"
+//! proxy_capture = false           # log proxied headers/bodies as proxy_capture events
+//! proxy_capture_redact = ["X-Api-Key"]   # besides Authorization and cookies
+//! event_log = true
+//!
+//! [[proxy]]                       # more rules, see `proxy.rs` for the options
+//! path = "/auth"
+//! target = "http://127.0.0.1:9000"
+//! rewrite = { replace = "/v1" }
+//!
+//! [headers]                       # or a list, for repeated names:
+//! "X-Custom" = "value"            # headers = ["Link: </a.css>; rel=preload", "Link: </b.js>; rel=preload"]
+//!
+//! [mounts]
+//! "/node_modules" = "./node_modules"
+//!
+//! # Named profiles, selected with `--profile <name>`
+//! [profiles.lan]
+//! https = true
+//! host = "0.0.0.0"
+//!
+//! [profiles.static]
+//! spa_file = ""                   # empty string clears a value set above
+//! proxy_base = ""
+//! ```
+//!
+//! Relative paths are resolved against the workspace directory.
+//! Precedence (highest first): CLI, `HOTPLATE_*` env, selected profile,
+//! project config, `.vscode/settings.json`, defaults.
+//! List and table values replace (not extend) the ones from lower layers.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// File names searched for in the workspace, in order.
+pub const PROJECT_CONFIG_FILES: &[&str] = &["hotplate.toml", ".hotplate/config.toml"];
+
+/// Contents of `hotplate.toml`. Unknown keys are rejected so typos don't go unnoticed.
+#[derive(Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub root: Option<String>,
+    pub https: Option<bool>,
+    pub cert: Option<String>,
+    pub key: Option<String>,
+    pub cert_hosts: Option<Vec<String>>,
+    pub client_ca: Option<String>,
+    pub http_port: Option<u16>,
+    pub redirect_http: Option<bool>,
+    pub h2c: Option<bool>,
+    pub compress: Option<bool>,
+    pub dir_listing: Option<bool>,
+    pub live_reload: Option<bool>,
+    pub full_reload: Option<bool>,
+    pub ignore: Option<Vec<String>>,
+    pub deny: Option<Vec<String>>,
+    pub allow_dotfiles: Option<bool>,
+    pub allow_ips: Option<Vec<String>>,
+    pub loopback_only: Option<bool>,
+    pub basic_auth: Option<String>,
+    pub watch_extensions: Option<Vec<String>>,
+    #[serde(alias = "file")]
+    pub spa_file: Option<String>,
+    /// `[[proxy]]` rules.
+    pub proxy: Option<Vec<crate::proxy::ProxyRule>>,
+    pub proxy_base: Option<String>,
+    pub proxy_target: Option<String>,
+    pub proxy_capture: Option<bool>,
+    pub proxy_capture_redact: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "header_list")]
+    pub headers: Option<Vec<(String, String)>>,
+    /// URL path → filesystem path.
+    pub mounts: Option<BTreeMap<String, String>>,
+    pub event_log: Option<bool>,
+    /// Named profiles (`[profiles.<name>]`), each overriding part of the top level.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ProjectConfig>,
+}
+
+impl ProjectConfig {
+    /// Remove and return the named profile, to be layered above the base settings.
+    pub fn take_profile(&mut self, name: &str) -> Result<ProjectConfig> {
+        let Some(p) = self.profiles.remove(name) else {
+            let available: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            anyhow::bail!(
+                "Unknown profile '{}' (available: {})",
+                name,
+                if available.is_empty() { "none".to_string() } else { available.join(", ") }
+            );
+        };
+        anyhow::ensure!(p.profiles.is_empty(), "Profile '{}' cannot define nested profiles", name);
+        Ok(p)
+    }
+}
+
+/// Response headers as a table (`"X-Custom" = "value"`) or as a list of
+/// `"Name: Value"` strings, which keeps repeated names in order.
+pub(crate) fn header_list<'de, D: Deserializer<'de>>(d: D) -> std::result::Result<Option<Vec<(String, String)>>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Headers {
+        Table(BTreeMap<String, String>),
+        List(Vec<String>),
+    }
+    let headers = match Option::<Headers>::deserialize(d)? {
+        None => return Ok(None),
+        Some(Headers::Table(table)) => table.into_iter().collect(),
+        Some(Headers::List(list)) => list
+            .iter()
+            .map(|h| match h.split_once(':') {
+                Some((name, value)) if !name.trim().is_empty() => Ok((name.trim().to_string(), value.trim().to_string())),
+                _ => Err(serde::de::Error::custom(format!("invalid header '{}' (expected \"Name: Value\")", h))),
+            })
+            .collect::<std::result::Result<_, _>>()?,
+    };
+    Ok(Some(headers))
+}
+
+// ───────────────────── Layered resolution ─────────────────────
+
+/// Where a resolved config value came from (reported by `--print-config`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    Cli,
+    Env,
+    Profile,
+    ConfigFile,
+    VsCode,
+    /// Dev cert generated by `--https`.
+    Generated,
+    Default,
+}
+
+/// Config layers in precedence order. Every source (CLI, env, profile, file,
+/// settings.json) is first converted to a [`ProjectConfig`], then each field
+/// is taken from the first layer that sets it.
+pub struct Layers {
+    layers: Vec<(ConfigSource, ProjectConfig)>,
+    sources: BTreeMap<&'static str, ConfigSource>,
+}
+
+impl Layers {
+    pub fn new(layers: Vec<(ConfigSource, ProjectConfig)>) -> Self {
+        Self { layers, sources: BTreeMap::new() }
+    }
+
+    /// First value set for `field`, recording its source (`Default` when unset).
+    pub fn get<T>(&mut self, field: &'static str, f: impl Fn(&ProjectConfig) -> Option<T>) -> Option<T> {
+        for (source, layer) in &self.layers {
+            if let Some(v) = f(layer) {
+                self.sources.insert(field, *source);
+                return Some(v);
+            }
+        }
+        self.sources.insert(field, ConfigSource::Default);
+        None
+    }
+
+    /// Like [`get`](Self::get), but stops after the first layer where `boundary`
+    /// holds — a layer that sets the `proxy` list hides lower-layer
+    /// `proxy_base` / `proxy_target` shorthands instead of adding to them.
+    pub fn get_down_to<T>(
+        &mut self,
+        field: &'static str,
+        boundary: impl Fn(&ProjectConfig) -> bool,
+        f: impl Fn(&ProjectConfig) -> Option<T>,
+    ) -> Option<T> {
+        for (source, layer) in &self.layers {
+            if let Some(v) = f(layer) {
+                self.sources.insert(field, *source);
+                return Some(v);
+            }
+            if boundary(layer) {
+                break;
+            }
+        }
+        self.sources.insert(field, ConfigSource::Default);
+        None
+    }
+
+    /// Recorded source of a field resolved earlier.
+    pub fn source(&self, field: &str) -> ConfigSource {
+        self.sources.get(field).copied().unwrap_or(ConfigSource::Default)
+    }
+
+    /// Override the recorded source of a field.
+    pub fn set_source(&mut self, field: &'static str, source: ConfigSource) {
+        self.sources.insert(field, source);
+    }
+
+    pub fn into_sources(self) -> BTreeMap<&'static str, ConfigSource> {
+        self.sources
+    }
+}
+
+/// Locate the project config file in `workspace` (first match of [`PROJECT_CONFIG_FILES`]).
+pub fn find_project_config(workspace: &Path) -> Option<PathBuf> {
+    PROJECT_CONFIG_FILES
+        .iter()
+        .map(|name| workspace.join(name))
+        .find(|p| p.is_file())
+}
+
+/// Parse a project config file. Unlike settings.json, a broken `hotplate.toml`
+/// is a hard error — it's our own file and silently ignoring it would be confusing.
+pub fn load_project_config(path: &Path) -> Result<ProjectConfig> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
@@ -0,0 +1,303 @@
+//! Proxy rules — forward URL prefixes to backend services.
+//!
+//! Each rule maps a path prefix to a target, e.g. `/api` → `http://127.0.0.1:8000`.
+//! The longest matching prefix wins. Rules come from `--proxy` (repeatable),
+//! `[[proxy]]` in `hotplate.toml`, `"hotplate.proxies"` in settings.json and the
+//! `proxy` argument of `hotplate_start`; `--proxy-base` / `--proxy-target` add
+//! one more rule with the defaults:
+//!
+//! ```toml
+//! [[proxy]]
+//! path = "/api"
+//! target = "http://127.0.0.1:8000"   # /api/users → /users (prefix stripped)
+//!
+//! [[proxy]]
+//! path = "/auth"
+//! target = "https://auth.internal:9443"
+//! rewrite = { replace = "/v1" }      # /auth/login → /v1/login
+//! change_origin = false              # keep the browser's Host header
+//! headers = { "X-Env" = "dev" }
+//! timeout = 10                       # seconds to wait for the response headers
+//!
+//! [[proxy]]
+//! path = "/media"
+//! target = "http://127.0.0.1:9000"
+//! rewrite = { regex = ["^/media/(.*)$", "/bucket/$1"] }
+//! ```
+//!
+//! On the command line the same rules read
+//! `--proxy "/auth=https://auth.internal:9443;rewrite=/v1;keep-host;header=X-Env: dev;timeout=10"`.
+//!
+//! Responses are adjusted so auth flows stay on the dev origin: absolute
+//! `Location` / `Content-Location` URLs pointing at the target become paths
+//! under the rule's prefix, and `Set-Cookie` loses its `Domain`, gets its `Path`
+//! mapped the same way and, on plain HTTP, drops `Secure`.
+
+use anyhow::{Context, Result};
+use axum::http::{HeaderName, HeaderValue};
+use regex_automata::meta::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// How the request path is changed before it is appended to the target.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathRewrite {
+    /// Drop the rule's prefix: `/api/users` → `/users` (the default).
+    #[default]
+    Strip,
+    /// Forward the path unchanged: `/api/users` → `/api/users`.
+    Keep,
+    /// Swap the prefix: `/api/users` with `"/v2"` → `/v2/users`.
+    Replace(String),
+    /// Regex over the whole path, `$1`/`$name` expanded: `["^/api/(.*)$", "/v2/$1"]`.
+    Regex(String, String),
+}
+
+impl PathRewrite {
+    fn is_default(&self) -> bool {
+        *self == PathRewrite::Strip
+    }
+}
+
+/// One prefix → target mapping.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProxyRule {
+    /// URL prefix, e.g. `/api`.
+    pub path: String,
+    /// Backend base URL, e.g. `http://127.0.0.1:8000`.
+    pub target: String,
+    #[serde(default, skip_serializing_if = "PathRewrite::is_default")]
+    pub rewrite: PathRewrite,
+    /// Send the target's host as `Host` instead of the browser's.
+    #[serde(default = "default_true", alias = "changeOrigin")]
+    pub change_origin: bool,
+    /// Extra request headers sent to the target.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// Seconds to wait for the target's response headers (streamed bodies are not limited).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl ProxyRule {
+    pub fn new(path: &str, target: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            target: target.to_string(),
+            rewrite: PathRewrite::Strip,
+            change_origin: true,
+            headers: BTreeMap::new(),
+            timeout: None,
+        }
+    }
+
+    /// Normalize the prefix (`api/` → `/api`) and check the target, rewrite and headers.
+    pub fn validate(mut self) -> Result<Self> {
+        let path = self.path.trim().trim_end_matches('/');
+        anyhow::ensure!(!path.is_empty(), "Proxy path must not be empty or '/'");
+        self.path = if path.starts_with('/') { path.to_string() } else { format!("/{}", path) };
+        anyhow::ensure!(self.path != "/__lr", "Proxy path /__lr is reserved for live reload");
+        // The router would read these as `:param` / `*wildcard` segments
+        anyhow::ensure!(
+            !self.path.split('/').any(|segment| segment.starts_with([':', '*'])),
+            "Proxy path {} must not have segments starting with ':' or '*'",
+            self.path
+        );
+
+        let target = self.target.trim().trim_end_matches('/');
+        anyhow::ensure!(
+            target.starts_with("http://") || target.starts_with("https://"),
+            "Proxy target for {} must be an http:// or https:// URL, got '{}'",
+            self.path,
+            self.target
+        );
+        self.target = target.to_string();
+
+        if let PathRewrite::Regex(ref pattern, _) = self.rewrite {
+            Regex::new(pattern).with_context(|| format!("Invalid rewrite regex for {}", self.path))?;
+        }
+        for (name, value) in &self.headers {
+            HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("Invalid header name '{}' in proxy rule for {}", name, self.path))?;
+            HeaderValue::from_str(value)
+                .with_context(|| format!("Invalid value for header {} in proxy rule for {}", name, self.path))?;
+        }
+        Ok(self)
+    }
+
+    /// Whether `path` falls under this rule's prefix.
+    pub fn matches(&self, path: &str) -> bool {
+        path.strip_prefix(&self.path)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    }
+}
+
+/// `PATH=TARGET[;OPTION...]`, options being `keep`, `rewrite=/v2`,
+/// `regex=PATTERN=>REPLACEMENT`, `keep-host`, `header=Name: Value` and `timeout=SECS`.
+impl FromStr for ProxyRule {
+    type Err = anyhow::Error;
+
+    fn from_str(spec: &str) -> Result<Self> {
+        let mut parts = spec.split(';');
+        let head = parts.next().unwrap_or_default();
+        let (path, target) = head
+            .split_once('=')
+            .with_context(|| format!("Invalid proxy rule '{}' (expected \"/path=http://host:port\")", spec))?;
+        let mut rule = ProxyRule::new(path, target);
+
+        for option in parts.map(str::trim).filter(|o| !o.is_empty()) {
+            let (key, value) = option.split_once('=').unwrap_or((option, ""));
+            match key.trim() {
+                "keep" => rule.rewrite = PathRewrite::Keep,
+                "rewrite" => rule.rewrite = PathRewrite::Replace(value.trim().to_string()),
+                "regex" => {
+                    let (pattern, to) = value
+                        .split_once("=>")
+                        .with_context(|| format!("Invalid regex option '{}' (expected PATTERN=>REPLACEMENT)", value))?;
+                    rule.rewrite = PathRewrite::Regex(pattern.trim().to_string(), to.trim().to_string());
+                }
+                "keep-host" => rule.change_origin = false,
+                "header" => {
+                    let (name, value) = value
+                        .split_once(':')
+                        .with_context(|| format!("Invalid header option '{}' (expected \"Name: Value\")", value))?;
+                    rule.headers.insert(name.trim().to_string(), value.trim().to_string());
+                }
+                "timeout" => {
+                    rule.timeout = Some(value.trim().parse().with_context(|| {
+                        format!("Invalid timeout '{}' (expected seconds)", value)
+                    })?);
+                }
+                other => anyhow::bail!(
+                    "Unknown proxy option '{}' (expected keep, rewrite, regex, keep-host, header or timeout)",
+                    other
+                ),
+            }
+        }
+        rule.validate()
+    }
+}
+
+/// A validated rule with its rewrite regex compiled, as used by the router.
+pub struct ProxyRoute {
+    pub rule: ProxyRule,
+    regex: Option<Regex>,
+}
+
+impl ProxyRoute {
+    /// `rule` must have passed [`ProxyRule::validate`].
+    pub fn new(rule: ProxyRule) -> Self {
+        let regex = match rule.rewrite {
+            PathRewrite::Regex(ref pattern, _) => Regex::new(pattern).ok(),
+            _ => None,
+        };
+        Self { rule, regex }
+    }
+
+    /// Upstream URL for a request path (under the rule's prefix) and query.
+    pub fn upstream_url(&self, path: &str, query: Option<&str>) -> String {
+        let rest = path.strip_prefix(&self.rule.path).unwrap_or(path);
+        let mut upstream = match self.rule.rewrite {
+            PathRewrite::Strip => rest.to_string(),
+            PathRewrite::Keep => path.to_string(),
+            PathRewrite::Replace(ref prefix) => format!("{}{}", prefix.trim_end_matches('/'), rest),
+            PathRewrite::Regex(_, ref to) => self.regex_rewrite(path, to),
+        };
+        if !upstream.starts_with('/') {
+            upstream.insert(0, '/');
+        }
+        match query {
+            Some(q) => format!("{}{}?{}", self.rule.target, upstream, q),
+            None => format!("{}{}", self.rule.target, upstream),
+        }
+    }
+
+    /// Map an absolute `Location` / `Content-Location` on the target back to a
+    /// path on the Hotplate origin (`http://127.0.0.1:8000/login?next=/` →
+    /// `/api/login?next=/`). URLs elsewhere, relative references and paths a
+    /// `regex` rewrite can't be undone for are left as they are.
+    pub fn rewrite_location(&self, location: &str) -> Option<String> {
+        let target = &self.rule.target;
+        let head = location.get(..target.len())?;
+        if !head.eq_ignore_ascii_case(target) {
+            return None;
+        }
+        let rest = &location[target.len()..];
+        // `http://host:8000` must not match `http://host:80001/...`
+        let split = rest.find(['?', '#']).unwrap_or(rest.len());
+        let (path, suffix) = rest.split_at(split);
+        if !path.is_empty() && !path.starts_with('/') {
+            return None;
+        }
+        let path = if path.is_empty() { "/" } else { path };
+        Some(format!("{}{}", self.browser_path(path)?, suffix))
+    }
+
+    /// Rewrite a `Set-Cookie` value for the Hotplate origin: drop `Domain`, map
+    /// `Path` back under the prefix (`Path=/` stays `/`, so sibling rules get the
+    /// cookie too) and, unless the browser is on HTTPS, drop `Secure` — turning
+    /// `SameSite=None`, which browsers reject without it, into `Lax`.
+    pub fn rewrite_cookie(&self, cookie: &str, https: bool) -> String {
+        let mut parts = cookie.split(';');
+        let mut out = vec![parts.next().unwrap_or_default().trim().to_string()];
+        for attr in parts.map(str::trim).filter(|a| !a.is_empty()) {
+            let (name, value) = attr.split_once('=').unwrap_or((attr, ""));
+            match name.trim().to_ascii_lowercase().as_str() {
+                "domain" => {}
+                "secure" if !https => {}
+                "samesite" if !https && value.trim().eq_ignore_ascii_case("none") => {
+                    out.push("SameSite=Lax".to_string());
+                }
+                "path" if value.trim() != "/" => match self.cookie_path(value.trim()) {
+                    Some(path) => out.push(format!("Path={}", path)),
+                    None => out.push(attr.to_string()),
+                },
+                _ => out.push(attr.to_string()),
+            }
+        }
+        out.join("; ")
+    }
+
+    /// Browser-side cookie path for an upstream one, relative to the target's
+    /// own path (`/bucket` of `http://store:9000/bucket`).
+    fn cookie_path(&self, upstream: &str) -> Option<String> {
+        let after_scheme = self.rule.target.split_once("://").map_or("", |(_, rest)| rest);
+        let base = after_scheme.find('/').map_or("", |i| &after_scheme[i..]);
+        let rest = upstream.strip_prefix(base)?;
+        match rest {
+            "" => self.browser_path("/"),
+            _ if rest.starts_with('/') => self.browser_path(rest),
+            _ => None,
+        }
+    }
+
+    /// Undo the path rewrite: an upstream path (relative to the target) → the browser-side path.
+    fn browser_path(&self, upstream: &str) -> Option<String> {
+        let prefix = &self.rule.path;
+        match self.rule.rewrite {
+            PathRewrite::Strip => Some(format!("{}{}", prefix, upstream)),
+            PathRewrite::Keep => Some(upstream.to_string()),
+            PathRewrite::Replace(ref with) => {
+                let rest = upstream.strip_prefix(with.trim_end_matches('/'))?;
+                (rest.is_empty() || rest.starts_with('/')).then(|| format!("{}{}", prefix, rest))
+            }
+            PathRewrite::Regex(..) => None,
+        }
+    }
+
+    /// Replace the first match of the rule's regex; unmatched paths pass unchanged.
+    fn regex_rewrite(&self, path: &str, to: &str) -> String {
+        let Some(ref regex) = self.regex else { return path.to_string() };
+        let mut caps = regex.create_captures();
+        regex.captures(path, &mut caps);
+        let Some(m) = caps.get_match() else { return path.to_string() };
+        format!("{}{}{}", &path[..m.start()], caps.interpolate_string(path, to), &path[m.end()..])
+    }
+}
@@ -1,28 +1,36 @@
 //! HTTP/HTTPS server with static files + WebSocket live reload + SPA fallback + proxy.
 
 use crate::inject::inject_livereload;
+use crate::access::{Denial, Granted};
 use crate::events::{EventData, EventLogger};
+use crate::capture::{Exchange, Redactor, Side, Tap};
+use crate::proxy::ProxyRoute;
 use crate::watcher;
 use crate::Config;
 
 use anyhow::Result;
+use axum_server::accept::Accept;
+use axum_server::tls_rustls::{RustlsAcceptor, RustlsConfig};
+use tower::Layer;
 use axum::{
     body::Body,
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        Request, State,
+        ConnectInfo, Query, Request, State,
     },
-    http::{header, Method, StatusCode},
+    http::{header, HeaderMap, Method, StatusCode, Uri},
     middleware::{self, Next},
     response::{IntoResponse, Response},
     routing::get,
     Router,
 };
-use std::{net::SocketAddr, sync::Arc};
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
 use std::time::Instant;
 use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::broadcast;
+use tower::ServiceExt;
 use tower_http::{
+    compression::CompressionLayer,
     cors::{Any, CorsLayer},
     services::{ServeDir, ServeFile},
     set_header::SetResponseHeaderLayer,
@@ -85,8 +93,6 @@ pub struct AppState {
     pub reload_tx: broadcast::Sender<String>,
     pub live_reload: bool,
     pub full_reload: bool,
-    pub proxy_base: Option<String>,
-    pub proxy_target: Option<String>,
     pub http_client: reqwest::Client,
     pub event_logger: EventLogger,
     pub client_counter: Arc<AtomicU64>,
@@ -100,6 +106,10 @@ pub struct AppState {
     pub dom_tx: tokio::sync::mpsc::UnboundedSender<(String, String)>,
     /// Channel for browser → MCP eval responses (id, result_json).
     pub eval_tx: tokio::sync::mpsc::UnboundedSender<(String, String)>,
+    /// Per-session secret required to open `/__lr` (embedded in the injected script).
+    pub lr_token: Arc<str>,
+    /// Header redaction for `--proxy-capture` (`None` = capture off).
+    pub capture: Option<Arc<Redactor>>,
 }
 
 // ───────────────────── WebSocket handler ─────────────────────
@@ -107,7 +117,34 @@ pub struct AppState {
 async fn ws_handler(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
-) -> impl IntoResponse {
+    Query(params): Query<HashMap<String, String>>,
+    req_headers: HeaderMap,
+    uri: Uri,
+) -> Response<Body> {
+    let token_ok = params
+        .get("token")
+        .is_some_and(|t| crate::access::token_matches(t, &state.lr_token));
+    if !token_ok {
+        return (StatusCode::FORBIDDEN, "Missing or invalid live reload token").into_response();
+    }
+
+    // Browsers always send Origin — reject pages from other sites, which could
+    // otherwise read the token from our HTML (CORS allows any origin)
+    if let Some(origin) = req_headers.get(header::ORIGIN) {
+        let host = req_headers
+            .get(header::HOST)
+            .and_then(|h| h.to_str().ok())
+            .or_else(|| uri.authority().map(|a| a.as_str()))
+            .unwrap_or("");
+        let same = origin
+            .to_str()
+            .is_ok_and(|o| crate::access::is_same_origin(o, host));
+        if !same {
+            eprintln!("  ⚠ Rejected /__lr connection from origin {:?}", origin);
+            return (StatusCode::FORBIDDEN, "Cross-origin live reload connection").into_response();
+        }
+    }
+
     ws.on_upgrade(|socket| handle_socket(socket, state))
 }
 
@@ -338,20 +375,26 @@ fn build_router(state: Arc<AppState>, config: &Config) -> Router {
         app = app.route("/__lr", get(ws_handler));
     }
 
-    // Proxy: forward /base/* to target server
-    if let (Some(ref base), Some(_)) = (&config.proxy_base, &config.proxy_target) {
-        let proxy_path = format!("{}/*rest", base.trim_end_matches('/'));
+    // Proxy: forward /base/* to each rule's target (more specific prefixes win)
+    for rule in &config.proxies {
+        let route = Arc::new(ProxyRoute::new(rule.clone()));
+        let tls = config.cert.is_some();
+        let handler = move |State(state): State<Arc<AppState>>, req: Request<Body>| {
+            let route = route.clone();
+            // The --http-port listener of a dual setup is plain HTTP
+            let https = tls && req.extensions().get::<PlainHttp>().is_none();
+            async move { proxy_handler(state, &route, https, req).await }
+        };
         // Also handle exact base path (no trailing subpath)
-        let proxy_path_exact = base.trim_end_matches('/').to_string();
         app = app
-            .route(&proxy_path, axum::routing::any(proxy_handler))
-            .route(&proxy_path_exact, axum::routing::any(proxy_handler));
+            .route(&format!("{}/*rest", rule.path), axum::routing::any(handler.clone()))
+            .route(&rule.path, axum::routing::any(handler));
     }
 
     // Mount extra directories at specific URL paths
     // e.g. --mount "/node_modules:./node_modules" serves ./node_modules at /node_modules
     for (url_path, fs_path) in &config.mounts {
-        let mount_service = ServeDir::new(fs_path).append_index_html_on_directories(true);
+        let mount_service = serve_dir(fs_path, config.compress);
         // nest_service strips the prefix before passing to ServeDir
         app = app.nest_service(url_path, mount_service);
     }
@@ -361,28 +404,68 @@ fn build_router(state: Arc<AppState>, config: &Config) -> Router {
 
     // Static file serving with optional SPA fallback
     if has_index || config.spa_file.is_some() {
-        let serve_dir = if let Some(ref spa_file) = config.spa_file {
-            ServeDir::new(&config.root)
-                .append_index_html_on_directories(true)
-                .fallback(ServeFile::new(config.root.join(spa_file)))
-        } else {
-            ServeDir::new(&config.root)
-                .append_index_html_on_directories(true)
-                .fallback(ServeFile::new(config.root.join("404.html")))
-        };
-        app = app.fallback_service(serve_dir);
+        let fallback = config.root.join(config.spa_file.as_deref().unwrap_or("404.html"));
+        app = app.fallback_service(
+            serve_dir(&config.root, config.compress).fallback(serve_file(&fallback, config.compress)),
+        );
     } else {
         // No index.html in root — serve static files normally but show welcome page on "/"
-        let serve_dir = ServeDir::new(&config.root)
-            .append_index_html_on_directories(true);
         app = app
             .route("/", get(welcome_handler))
-            .fallback_service(serve_dir);
+            .fallback_service(serve_dir(&config.root, config.compress));
     }
 
+    let filter = Arc::new(crate::access::PathFilter::new(&config.deny_patterns, config.allow_dotfiles));
+
+    // Directory index for folders without index.html (root and mounts).
+    // Proxied paths and /__lr never show a listing.
+    let proxies = Arc::new(config.proxies.clone());
+    if config.dir_listing {
+        let index = Arc::new(crate::listing::DirIndex::new(
+            &config.root,
+            &config.mounts,
+            filter.clone(),
+            &config.ignore_patterns,
+        ));
+        let proxies = proxies.clone();
+        app = app.layer(middleware::from_fn(move |req: Request<Body>, next: Next| {
+            let index = index.clone();
+            let proxies = proxies.clone();
+            async move {
+                let path = req.uri().path();
+                if path == "/__lr" || proxies.iter().any(|rule| rule.matches(path)) {
+                    return next.run(req).await;
+                }
+                let (parts, body) = req.into_parts();
+                match index.respond(&parts).await {
+                    Some(listing) => listing,
+                    None => next.run(Request::from_parts(parts, body)).await,
+                }
+            }
+        }));
+    }
+
+    // Never serve .hotplate/ internals, dotfiles or sensitive files.
+    // Proxied paths are left to the backend.
+    app = app.layer(middleware::from_fn(move |req: Request<Body>, next: Next| {
+        let filter = filter.clone();
+        let proxies = proxies.clone();
+        async move {
+            let path = req.uri().path();
+            let proxied = proxies.iter().any(|rule| rule.matches(path));
+            if !proxied && filter.is_denied(path) {
+                return StatusCode::NOT_FOUND.into_response();
+            }
+            next.run(req).await
+        }
+    }));
+
     // Middleware stack (applied bottom-up)
     if config.live_reload {
-        app = app.layer(middleware::from_fn(inject_livereload));
+        let token = state.lr_token.clone();
+        app = app.layer(middleware::from_fn(move |req: Request<Body>, next: Next| {
+            inject_livereload(req, next, token.clone())
+        }));
     }
 
     // Custom response headers
@@ -409,8 +492,15 @@ fn build_router(state: Arc<AppState>, config: &Config) -> Router {
                 let hdrs = headers_vec.clone();
                 async move {
                     let mut resp = next.run(req).await;
+                    // The first of each name replaces the response's own; repeats are added
+                    let mut seen = Vec::new();
                     for (name, value) in hdrs {
-                        resp.headers_mut().insert(name, value);
+                        if seen.contains(&name) {
+                            resp.headers_mut().append(name, value);
+                        } else {
+                            resp.headers_mut().insert(name.clone(), value);
+                            seen.push(name);
+                        }
                     }
                     resp
                 }
@@ -418,6 +508,80 @@ fn build_router(state: Arc<AppState>, config: &Config) -> Router {
         }
     }
 
+    // Compression — outside the injection layer, so injected HTML is compressed
+    // too. Already-encoded responses (precompressed files, upstream gzip) pass through.
+    if config.compress {
+        app = app.layer(CompressionLayer::new());
+    }
+
+    // Dual HTTP+HTTPS: send plain-HTTP requests to the HTTPS port, except the
+    // live reload socket and the proxy (tools that must stay on plain HTTP).
+    if config.redirect_http && config.cert.is_some() && config.http_port.is_some() {
+        let https_port = config.port;
+        let mut exempt = vec!["/__lr".to_string()];
+        exempt.extend(config.proxies.iter().map(|rule| rule.path.clone()));
+        app = app.layer(middleware::from_fn(move |req: Request<Body>, next: Next| {
+            let exempt = exempt.clone();
+            async move {
+                let path = req.uri().path();
+                let is_exempt = exempt
+                    .iter()
+                    .any(|p| path == p || path.starts_with(&format!("{}/", p)));
+                if req.extensions().get::<PlainHttp>().is_none() || is_exempt {
+                    return next.run(req).await;
+                }
+                https_redirect(&req, https_port)
+            }
+        }));
+    }
+
+    // Access control (outside everything but logging): loopback-only, IP allowlist, basic auth
+    if let Some(policy) =
+        crate::access::AccessPolicy::new(config.loopback_only, &config.allow_ips, config.basic_auth.clone())
+    {
+        let policy = Arc::new(policy);
+        let logger = state.event_logger.clone();
+        app = app.layer(middleware::from_fn(move |mut req: Request<Body>, next: Next| {
+            let policy = policy.clone();
+            let logger = logger.clone();
+            async move {
+                let peer = req
+                    .extensions()
+                    .get::<ConnectInfo<SocketAddr>>()
+                    .map(|ConnectInfo(addr)| addr.ip());
+                let denial = match policy.check(peer, req.headers(), req.uri().path()) {
+                    Ok(granted) => {
+                        policy.strip_credentials(req.headers_mut());
+                        let mut resp = next.run(req).await;
+                        if granted == Granted::Credentials {
+                            if let Ok(cookie) = header::HeaderValue::from_str(&policy.session_cookie()) {
+                                resp.headers_mut().append(header::SET_COOKIE, cookie);
+                            }
+                        }
+                        return resp;
+                    }
+                    Err(denial) => denial,
+                };
+                logger.log(EventData::AccessDenied {
+                    ip: peer.map(|ip| ip.to_string()).unwrap_or_default(),
+                    method: req.method().to_string(),
+                    path: req.uri().path().to_string(),
+                    reason: denial.reason().to_string(),
+                });
+                match denial {
+                    Denial::AuthRequired | Denial::BadCredentials => Response::builder()
+                        .status(StatusCode::UNAUTHORIZED)
+                        .header(header::WWW_AUTHENTICATE, "Basic realm=\"hotplate\", charset=\"UTF-8\"")
+                        .body(Body::from("Authentication required"))
+                        .unwrap_or_default(),
+                    Denial::LoopbackOnly | Denial::IpNotAllowed => {
+                        (StatusCode::FORBIDDEN, "Access denied").into_response()
+                    }
+                }
+            }
+        }));
+    }
+
     // Cache-Control: no-cache — browser must revalidate every request (304 still works).
     // Prevents stale JS/images after live-reload triggers location.reload().
     //
@@ -428,6 +592,8 @@ fn build_router(state: Arc<AppState>, config: &Config) -> Router {
         async move {
             let method = req.method().to_string();
             let path = req.uri().path().to_string();
+            let version = format!("{:?}", req.version());
+            let client_cert = client_cert_subject(&req).map(str::to_string);
             // Skip WebSocket upgrade and internal paths from logging
             let should_log = !path.starts_with("/__lr");
             let start = Instant::now();
@@ -436,8 +602,10 @@ fn build_router(state: Arc<AppState>, config: &Config) -> Router {
                 logger.log(EventData::HttpRequest {
                     method,
                     path,
+                    version,
                     status: resp.status().as_u16(),
                     duration_ms: start.elapsed().as_millis() as u64,
+                    client_cert,
                 });
             }
             resp
@@ -451,6 +619,53 @@ fn build_router(state: Arc<AppState>, config: &Config) -> Router {
     .with_state(state)
 }
 
+/// `ServeDir` with index.html for directories; with `compress`, an existing
+/// `app.js.br` / `.zst` / `.gz` is sent instead of `app.js` if the client accepts it.
+fn serve_dir(path: &std::path::Path, compress: bool) -> ServeDir {
+    let dir = ServeDir::new(path).append_index_html_on_directories(true);
+    if compress {
+        dir.precompressed_br().precompressed_zstd().precompressed_gzip()
+    } else {
+        dir
+    }
+}
+
+/// `ServeFile` counterpart of [`serve_dir`] (SPA / 404.html fallback).
+fn serve_file(path: &std::path::Path, compress: bool) -> ServeFile {
+    let file = ServeFile::new(path);
+    if compress {
+        file.precompressed_br().precompressed_zstd().precompressed_gzip()
+    } else {
+        file
+    }
+}
+
+/// Marks requests that arrived on the plain-HTTP listener in dual HTTP+HTTPS mode.
+#[derive(Clone, Copy)]
+struct PlainHttp;
+
+/// 307 to the same host and path on the HTTPS port (method and body preserved).
+fn https_redirect(req: &Request<Body>, https_port: u16) -> Response<Body> {
+    // HTTP/2 has no Host header — the authority is in the URI
+    let host = req
+        .headers()
+        .get(header::HOST)
+        .and_then(|h| h.to_str().ok())
+        .or_else(|| req.uri().authority().map(|a| a.as_str()))
+        .unwrap_or("localhost");
+    // Strip the port, keeping IPv6 brackets ("[::1]:5501" → "[::1]")
+    let host = match host.rfind(']') {
+        Some(end) => &host[..=end],
+        None => host.split(':').next().unwrap_or(host),
+    };
+    let path = req.uri().path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    Response::builder()
+        .status(StatusCode::TEMPORARY_REDIRECT)
+        .header(header::LOCATION, format!("https://{}:{}{}", host, https_port, path))
+        .body(Body::empty())
+        .unwrap_or_default()
+}
+
 // ───────────────────── Welcome page handler ─────────────────────
 
 /// Serve the built-in welcome page (when root has no index.html).
@@ -464,29 +679,28 @@ async fn welcome_handler() -> impl IntoResponse {
 
 // ───────────────────── Proxy handler ─────────────────────
 
-/// Forward requests to the configured proxy target.
-/// Preserves method, headers, query string, and body.
+/// Forward requests to a proxy rule's target.
+/// Preserves method, headers, query string, and body. Both bodies are streamed,
+/// so large uploads/downloads and server-sent events pass through as they arrive.
+/// WebSocket upgrades are tunneled to the target (see [`tunnel_upgrade`]).
+/// Redirects and cookies are rewritten for the Hotplate origin (`https`: the
+/// browser's connection is TLS).
 async fn proxy_handler(
-    State(state): State<Arc<AppState>>,
-    req: Request<Body>,
+    state: Arc<AppState>,
+    route: &ProxyRoute,
+    https: bool,
+    mut req: Request<Body>,
 ) -> Response<Body> {
-    let (Some(ref base), Some(ref target)) = (&state.proxy_base, &state.proxy_target) else {
-        return (StatusCode::BAD_GATEWAY, "Proxy not configured").into_response();
-    };
+    let rule = &route.rule;
 
-    // Build target URL: strip proxy base, keep remaining path + query
+    // Build target URL: rewrite the path (strip the prefix by default), keep the query
     let uri = req.uri().clone();
-    let path = uri.path();
-    let remaining = path
-        .strip_prefix(base.trim_end_matches('/'))
-        .unwrap_or(path);
-    let remaining = if remaining.is_empty() { "/" } else { remaining };
-
-    let target_url = if let Some(query) = uri.query() {
-        format!("{}{}?{}", target.trim_end_matches('/'), remaining, query)
-    } else {
-        format!("{}{}", target.trim_end_matches('/'), remaining)
-    };
+    let target_url = route.upstream_url(uri.path(), uri.query());
+
+    // WebSocket (or other protocol) upgrade: forward the handshake headers and
+    // keep hold of the client connection for the tunnel
+    let is_upgrade = is_upgrade_request(req.headers());
+    let client_upgrade = is_upgrade.then(|| hyper::upgrade::on(&mut req));
 
     // Forward the request
     let method = req.method().clone();
@@ -495,50 +709,303 @@ async fn proxy_handler(
         &target_url,
     );
 
-    // Copy headers (skip host — reqwest sets it automatically)
+    // Copy headers (skip host — reqwest sets the target's unless the rule keeps the browser's).
+    // The client-cert header is only ever set from the verified TLS session.
+    if !rule.change_origin {
+        let host = req
+            .headers()
+            .get(header::HOST)
+            .and_then(|h| h.to_str().ok())
+            .or_else(|| uri.authority().map(|a| a.as_str())); // HTTP/2 has :authority instead
+        if let Some(host) = host {
+            builder = builder.header(header::HOST.as_str(), host);
+        }
+    }
+    // With --proxy-capture the target is not offered compression, so captured
+    // bodies stay readable (`--compress` still encodes toward the browser).
+    let capturing = state.capture.is_some();
     for (key, value) in req.headers() {
-        if key != header::HOST {
+        let handshake = is_upgrade && (key == header::CONNECTION || key == header::UPGRADE);
+        let overridden = rule.headers.keys().any(|name| name.eq_ignore_ascii_case(key.as_str()));
+        if key != header::HOST
+            && key != CLIENT_CERT_HEADER
+            && !(capturing && key == header::ACCEPT_ENCODING)
+            && !overridden
+            && (handshake || !is_hop_by_hop(key))
+        {
             if let Ok(v) = value.to_str() {
                 builder = builder.header(key.as_str(), v);
             }
         }
     }
+    if let Some(subject) = client_cert_subject(&req) {
+        let subject = percent_encoding::utf8_percent_encode(subject, CLIENT_CERT_ESCAPE).to_string();
+        builder = builder.header(CLIENT_CERT_HEADER, subject);
+    }
+    for (name, value) in &rule.headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+
+    let mut upstream_req = match builder.build() {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("  ⚠ Proxy error: {}", e);
+            return (StatusCode::BAD_GATEWAY, format!("Proxy error: {}", e)).into_response();
+        }
+    };
 
-    // Copy body for non-GET/HEAD methods
+    // --proxy-capture: record what is actually sent upstream
+    let exchange = state.capture.as_ref().map(|redactor| {
+        let path = uri.path_and_query().map_or(uri.path(), |pq| pq.as_str());
+        Exchange::start(redactor, method.as_str(), path, &target_url, upstream_req.headers())
+    });
+
+    // Stream the body for non-GET/HEAD methods (no size cap; chunked uploads stay chunked)
     if method != Method::GET && method != Method::HEAD {
-        let body_bytes = match axum::body::to_bytes(req.into_body(), 10 * 1024 * 1024).await {
-            Ok(b) => b,
-            Err(_) => return (StatusCode::BAD_REQUEST, "Failed to read request body").into_response(),
-        };
-        builder = builder.body(body_bytes);
+        let body = req.into_body().into_data_stream();
+        *upstream_req.body_mut() = Some(match exchange {
+            Some(ref ex) => {
+                let logger = state.event_logger.clone();
+                reqwest::Body::wrap_stream(Tap::new(body, ex.clone(), Side::Request, logger))
+            }
+            None => reqwest::Body::wrap_stream(body),
+        });
     }
 
-    // Execute proxied request
-    match builder.send().await {
+    // Execute proxied request; the timeout covers the response headers, not streamed bodies
+    let send = state.http_client.execute(upstream_req);
+    let sent = match rule.timeout {
+        Some(secs) => match tokio::time::timeout(std::time::Duration::from_secs(secs), send).await {
+            Ok(sent) => sent,
+            Err(_) => {
+                eprintln!("  ⚠ Proxy timeout: {} after {}s", target_url, secs);
+                let error = format!("Proxy timeout after {}s", secs);
+                if let Some(ref ex) = exchange {
+                    Exchange::fail(ex, &state.event_logger, StatusCode::GATEWAY_TIMEOUT.as_u16(), error.clone());
+                }
+                return (StatusCode::GATEWAY_TIMEOUT, error).into_response();
+            }
+        },
+        None => send.await,
+    };
+    match sent {
         Ok(proxy_resp) => {
             let status = StatusCode::from_u16(proxy_resp.status().as_u16())
                 .unwrap_or(StatusCode::BAD_GATEWAY);
+            if let (Some(ref ex), Some(redactor)) = (&exchange, &state.capture) {
+                Exchange::set_response(ex, redactor, status.as_u16(), proxy_resp.headers());
+            }
+            if let (StatusCode::SWITCHING_PROTOCOLS, Some(client_upgrade)) = (status, client_upgrade) {
+                if let Some(ref ex) = exchange {
+                    Exchange::finish(ex, &state.event_logger);
+                }
+                return tunnel_upgrade(&state, client_upgrade, proxy_resp, uri.path(), &target_url);
+            }
             let mut response = Response::builder().status(status);
 
-            // Copy response headers
+            // Copy response headers (connection-level ones are invalid over HTTP/2).
+            // Without Content-Length the body goes out chunked again, as upstream sent it.
+            // Redirects to the target and its cookies are pointed back at this origin.
             for (key, value) in proxy_resp.headers() {
-                response = response.header(key.as_str(), value.as_bytes());
+                if is_hop_by_hop(key) {
+                    continue;
+                }
+                let rewritten = match (key, value.to_str()) {
+                    (&header::LOCATION | &header::CONTENT_LOCATION, Ok(v)) => route.rewrite_location(v),
+                    (&header::SET_COOKIE, Ok(v)) => Some(route.rewrite_cookie(v, https)),
+                    _ => None,
+                };
+                match rewritten {
+                    Some(v) => response = response.header(key.as_str(), v),
+                    None => response = response.header(key.as_str(), value.as_bytes()),
+                }
             }
 
-            match proxy_resp.bytes().await {
-                Ok(body) => response.body(Body::from(body)).unwrap_or_else(|_| {
-                    (StatusCode::BAD_GATEWAY, "Failed to build response").into_response()
-                }),
-                Err(_) => (StatusCode::BAD_GATEWAY, "Failed to read proxy response").into_response(),
-            }
+            // Chunks are forwarded as they arrive; `--compress` leaves
+            // text/event-stream alone, so server-sent events are never held back.
+            let body = match exchange {
+                Some(ex) => {
+                    let stream = Box::pin(proxy_resp.bytes_stream());
+                    Body::from_stream(Tap::new(stream, ex, Side::Response, state.event_logger.clone()))
+                }
+                None => Body::from_stream(proxy_resp.bytes_stream()),
+            };
+            response
+                .body(body)
+                .unwrap_or_else(|_| (StatusCode::BAD_GATEWAY, "Failed to build response").into_response())
         }
         Err(e) => {
             eprintln!("  ⚠ Proxy error: {}", e);
+            if let Some(ref ex) = exchange {
+                Exchange::fail(ex, &state.event_logger, StatusCode::BAD_GATEWAY.as_u16(), e.to_string());
+            }
             (StatusCode::BAD_GATEWAY, format!("Proxy error: {}", e)).into_response()
         }
     }
 }
 
+/// Whether the request asks to switch protocols (`Connection: upgrade` + `Upgrade`).
+fn is_upgrade_request(headers: &HeaderMap) -> bool {
+    let connection_upgrade = headers
+        .get_all(header::CONNECTION)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|token| token.trim().eq_ignore_ascii_case("upgrade"));
+    connection_upgrade && headers.contains_key(header::UPGRADE)
+}
+
+/// Answer the client's upgrade with the upstream's `101` and splice the two
+/// connections together. Frames are copied as raw bytes, so subprotocols and
+/// extensions (`Sec-WebSocket-Protocol`, `permessage-deflate`) are whatever
+/// the client and upstream agreed on.
+fn tunnel_upgrade(
+    state: &AppState,
+    client_upgrade: hyper::upgrade::OnUpgrade,
+    proxy_resp: reqwest::Response,
+    path: &str,
+    target_url: &str,
+) -> Response<Body> {
+    let mut response = Response::builder().status(StatusCode::SWITCHING_PROTOCOLS);
+    for (key, value) in proxy_resp.headers() {
+        response = response.header(key.as_str(), value.as_bytes());
+    }
+    let protocol = proxy_resp
+        .headers()
+        .get(header::SEC_WEBSOCKET_PROTOCOL)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+
+    let logger = state.event_logger.clone();
+    let path = path.to_string();
+    let target = target_url.to_string();
+    tokio::spawn(async move {
+        let started = Instant::now();
+        let (client, upstream) = tokio::join!(client_upgrade, proxy_resp.upgrade());
+        let upgraded = match (client, upstream) {
+            (Ok(client), Ok(upstream)) => Ok((reqwest::Upgraded::from(client), upstream)),
+            (Err(e), _) => Err(format!("client upgrade failed: {}", e)),
+            (_, Err(e)) => Err(format!("upstream upgrade failed: {}", e)),
+        };
+        let (client, mut upstream) = match upgraded {
+            Ok(pair) => pair,
+            // The client already has its 101; still record why the tunnel never opened
+            Err(error) => {
+                eprintln!("  ⚠ Proxy {}", error);
+                return logger.log(EventData::ProxyWsDisconnect {
+                    path,
+                    duration_ms: started.elapsed().as_millis() as u64,
+                    bytes_up: 0,
+                    bytes_down: 0,
+                    error: Some(error),
+                });
+            }
+        };
+
+        logger.log(EventData::ProxyWsConnect {
+            path: path.clone(),
+            target,
+            protocol,
+        });
+        let started = Instant::now();
+        let mut client = Counted::new(client);
+        let error = tokio::io::copy_bidirectional(&mut client, &mut upstream)
+            .await
+            .err()
+            .map(|e| e.to_string());
+        logger.log(EventData::ProxyWsDisconnect {
+            path,
+            duration_ms: started.elapsed().as_millis() as u64,
+            bytes_up: client.read,
+            bytes_down: client.written,
+            error,
+        });
+    });
+
+    response
+        .body(Body::empty())
+        .unwrap_or_else(|_| (StatusCode::BAD_GATEWAY, "Failed to build response").into_response())
+}
+
+/// Counts the bytes read from and written to a stream, so a tunnel that ends
+/// in an error still reports the traffic so far.
+struct Counted<S> {
+    inner: S,
+    read: u64,
+    written: u64,
+}
+
+impl<S> Counted<S> {
+    fn new(inner: S) -> Self {
+        Self { inner, read: 0, written: 0 }
+    }
+}
+
+impl<S: tokio::io::AsyncRead + Unpin> tokio::io::AsyncRead for Counted<S> {
+    fn poll_read(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let before = buf.filled().len();
+        let poll = std::pin::Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let std::task::Poll::Ready(Ok(())) = poll {
+            self.read += (buf.filled().len() - before) as u64;
+        }
+        poll
+    }
+}
+
+impl<S: tokio::io::AsyncWrite + Unpin> tokio::io::AsyncWrite for Counted<S> {
+    fn poll_write(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        let poll = std::pin::Pin::new(&mut self.inner).poll_write(cx, buf);
+        if let std::task::Poll::Ready(Ok(n)) = poll {
+            self.written += n as u64;
+        }
+        poll
+    }
+
+    fn poll_flush(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// Subject of the verified client cert, forwarded to proxy upstreams (mTLS).
+const CLIENT_CERT_HEADER: &str = "x-client-cert-subject";
+
+/// Escaped in that header: `%`, control characters and (always) non-ASCII, so
+/// UTF8String names like `CN=José` still make a valid header value.
+const CLIENT_CERT_ESCAPE: &percent_encoding::AsciiSet = &percent_encoding::CONTROLS.add(b'%');
+
+/// The header redactor when `--proxy-capture` is on.
+fn proxy_capture(config: &Config) -> Option<Arc<Redactor>> {
+    config
+        .proxy_capture
+        .then(|| Arc::new(Redactor::new(&config.proxy_capture_redact)))
+}
+
+/// Connection-level headers that must not be forwarded by a proxy (RFC 9110 §7.6.1).
+fn is_hop_by_hop(name: &header::HeaderName) -> bool {
+    matches!(
+        name.as_str(),
+        "connection"
+            | "keep-alive"
+            | "proxy-connection"
+            | "transfer-encoding"
+            | "te"
+            | "trailer"
+            | "upgrade"
+    )
+}
+
 // ───────────────────── Startup banner ─────────────────────
 
 fn print_banner(config: &Config) {
@@ -552,17 +1019,52 @@ fn print_banner(config: &Config) {
     println!("  🔥 hotplate v{}", env!("CARGO_PKG_VERSION"));
     println!("  ─────────────────────────────────────");
     println!("  📂 Root:    {}", config.root.display());
+    if let Some(ref file) = config.config_file {
+        println!("  ⚙  Config:  {}", file.display());
+    }
+    if let Some(ref profile) = config.profile {
+        println!("  🎛  Profile: {}", profile);
+    }
     println!("  🔗 Local:   {}://localhost:{}", scheme, config.port);
     if config.host == "0.0.0.0" {
         // Show LAN addresses
         if let Ok(addrs) = local_ip_addresses() {
             for addr in addrs {
-                println!("  🌐 Network: {}://{}:{}", scheme, addr, config.port);
+                if config.loopback_only {
+                    println!("  🌐 Network: {}://{}:{} (disabled — loopback only)", scheme, addr, config.port);
+                } else {
+                    println!("  🌐 Network: {}://{}:{}", scheme, addr, config.port);
+                }
             }
         }
     }
     if config.cert.is_some() {
-        println!("  🔒 HTTPS:   enabled");
+        // Names from --cert-host (e.g. via /etc/hosts) — only the generated dev
+        // cert is known to cover them. Wildcards aren't URLs, so name the pattern.
+        let dev_cert = crate::cert::dev_cert_paths(&config.workspace).0;
+        let hosts = if config.cert.as_ref() == Some(&dev_cert) { config.cert_hosts.as_slice() } else { &[] };
+        for host in hosts {
+            if let Some(domain) = host.strip_prefix("*.") {
+                println!("  🏷  Host:    {}://<any>.{}:{}", scheme, domain, config.port);
+                continue;
+            }
+            let host = match host.parse::<std::net::Ipv6Addr>() {
+                Ok(_) => format!("[{}]", host),
+                Err(_) => host.clone(),
+            };
+            println!("  🏷  Host:    {}://{}:{}", scheme, host, config.port);
+        }
+        println!("  🔒 HTTPS:   enabled (HTTP/2 + HTTP/1.1)");
+        if let Some(ref ca) = config.client_ca {
+            println!("  🪪 mTLS:    client certs required ({})", ca.display());
+        }
+        if let Some(http_port) = config.http_port {
+            let note = if config.redirect_http { " (redirects to HTTPS)" } else { "" };
+            println!("  🔓 HTTP:    http://localhost:{}{}", http_port, note);
+        }
+    }
+    if config.h2c && (config.cert.is_none() || config.http_port.is_some()) {
+        println!("  ⚡ h2c:     HTTP/2 without TLS on the plain-HTTP port");
     }
     let reload_mode = if !config.live_reload {
         "OFF"
@@ -572,8 +1074,23 @@ fn print_banner(config: &Config) {
         "ON (CSS hot swap)"
     };
     println!("  🔄 Reload:  {}", reload_mode);
-    if let (Some(ref base), Some(ref target)) = (&config.proxy_base, &config.proxy_target) {
-        println!("  🔀 Proxy:   {} → {}", base, target);
+    if config.compress {
+        println!("  🗜  Compress: br, zstd, gzip (+ precompressed files)");
+    }
+    for rule in &config.proxies {
+        println!("  🔀 Proxy:   {} → {}", rule.path, rule.target);
+    }
+    let mut access = Vec::new();
+    if config.loopback_only {
+        access.push("loopback only".to_string());
+    } else if !config.allow_ips.is_empty() {
+        access.push(format!("loopback + {}", config.allow_ips.join(", ")));
+    }
+    if let Some(user) = config.basic_auth.as_deref().and_then(|a| a.split(':').next()) {
+        access.push(format!("basic auth (user {})", user));
+    }
+    if !access.is_empty() {
+        println!("  🛡  Access:  {}", access.join(", "));
     }
     if !config.mounts.is_empty() {
         for (url_path, fs_path) in &config.mounts {
@@ -618,7 +1135,13 @@ pub struct ExternalChannels {
 ///
 /// If `ext` is `Some`, uses the pre-created channels (MCP mode).
 /// Otherwise creates fresh ones (standalone mode).
-pub async fn run(mut config: Config, ext: Option<ExternalChannels>) -> Result<()> {
+/// If `reloader` is `Some`, config file changes are applied without a restart.
+pub async fn run(
+    mut config: Config,
+    ext: Option<ExternalChannels>,
+    reloader: Option<ConfigReloader>,
+) -> Result<()> {
+    let interactive = ext.is_none();
     let (reload_tx, screenshot_tx, dom_tx, eval_tx, console_logs, network_logs) = match ext {
         Some(e) => (e.reload_tx, e.screenshot_tx, e.dom_tx, e.eval_tx, e.console_logs, e.network_logs),
         None => {
@@ -636,12 +1159,16 @@ pub async fn run(mut config: Config, ext: Option<ExternalChannels>) -> Result<()
     let event_logger = if config.event_log {
         EventLogger::new(&config.workspace)
     } else {
+        if config.proxy_capture {
+            eprintln!("  ⚠ --proxy-capture has no effect with --no-event-log");
+        }
         EventLogger::noop()
     };
 
     // HTTP client for proxy (reusable connection pool)
     let http_client = reqwest::Client::builder()
         .danger_accept_invalid_certs(true) // dev mode — proxy targets may use self-signed
+        .redirect(reqwest::redirect::Policy::none()) // the browser follows redirects, not the proxy
         .build()
         .unwrap_or_default();
 
@@ -649,8 +1176,6 @@ pub async fn run(mut config: Config, ext: Option<ExternalChannels>) -> Result<()
         reload_tx: reload_tx.clone(),
         live_reload: config.live_reload,
         full_reload: config.full_reload,
-        proxy_base: config.proxy_base.clone(),
-        proxy_target: config.proxy_target.clone(),
         http_client,
         event_logger: event_logger.clone(),
         client_counter: Arc::new(AtomicU64::new(0)),
@@ -659,6 +1184,8 @@ pub async fn run(mut config: Config, ext: Option<ExternalChannels>) -> Result<()
         eval_tx,
         console_logs,
         network_logs,
+        lr_token: crate::access::new_token().into(),
+        capture: proxy_capture(&config),
     });
 
     // Log server start event
@@ -670,127 +1197,406 @@ pub async fn run(mut config: Config, ext: Option<ExternalChannels>) -> Result<()
         live_reload: config.live_reload,
     });
 
-    // Start file watcher
-    if config.live_reload {
-        watcher::spawn(config.root.clone(), reload_tx, &config.ignore_patterns, &config.watch_extensions, event_logger)?;
-    }
-
-    let app = build_router(state, &config);
+    // Start file watcher (stops when the handle is dropped)
+    let file_watch = spawn_file_watcher(&config, &reload_tx, &event_logger)?;
+
+    // Load TLS before binding, so a passphrase prompt or a bad key fails early.
+    // Under MCP, stdin carries JSON-RPC — never prompt there.
+    let tls_config = match (&config.cert, &config.key) {
+        (Some(cert), Some(key)) => Some(
+            crate::cert::load_rustls_config(
+                cert,
+                key,
+                config.client_ca.as_deref(),
+                &mut config.key_passphrase,
+                interactive,
+            )?,
+        ),
+        _ => None,
+    };
 
     // Bind HTTP or HTTPS — with auto port increment on AddrInUse
-    match (&config.cert, &config.key) {
-        (Some(cert), Some(key)) => {
-            let tls_config =
-                axum_server::tls_rustls::RustlsConfig::from_pem_file(cert, key).await?;
-
-            let original_port = config.port;
-            let mut bound = None;
-
-            for attempt in 0..=MAX_PORT_RETRIES {
-                let try_port = config.port + attempt;
-                if try_port < config.port {
-                    break; // overflow guard
-                }
-                let addr: SocketAddr = format!("{}:{}", config.host, try_port).parse()?;
-
-                // Quick check: can we bind this port?
-                match std::net::TcpListener::bind(addr) {
-                    Ok(probe) => {
-                        drop(probe); // release immediately so axum_server can bind
-                        config.port = try_port;
-                        bound = Some(addr);
-                        break;
-                    }
-                    Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
-                        if attempt == 0 {
-                            eprintln!(
-                                "  ⚠ Port {} is in use, searching for an available port...",
-                                try_port
-                            );
-                        }
-                        continue;
-                    }
-                    Err(e) => return Err(e.into()),
-                }
-            }
+    let original_port = config.port;
+    let (listener, port) = bind_with_retry(&config.host, config.port)?;
+    config.port = port;
+    if config.port != original_port {
+        println!(
+            "  ℹ Port {} was in use, switched to port {}.",
+            original_port, config.port
+        );
+    }
 
-            let addr = bound.ok_or_else(|| {
-                anyhow::anyhow!(
-                    "Ports {}-{} are all in use. Please free a port or choose a different one.",
-                    original_port,
-                    original_port + MAX_PORT_RETRIES
-                )
-            })?;
-
-            if config.port != original_port {
-                println!(
-                    "  ℹ Port {} was in use, switched to port {}.",
-                    original_port, config.port
-                );
+    // Dual mode: an extra plain-HTTP listener next to HTTPS
+    let http_listener = match config.http_port {
+        Some(http_port) if tls_config.is_some() => {
+            let (listener, port) = bind_with_retry(&config.host, http_port)?;
+            if port != http_port {
+                println!("  ℹ HTTP port {} was in use, switched to port {}.", http_port, port);
             }
+            config.http_port = Some(port);
+            Some(listener)
+        }
+        _ => None,
+    };
+
+    // The served router is swapped in place when the config is reloaded.
+    // Built after binding so it knows the final ports.
+    let router = Arc::new(std::sync::RwLock::new(build_router(state.clone(), &config)));
+    let app = {
+        let router = router.clone();
+        Router::new().fallback_service(tower::service_fn(move |req: Request<Body>| {
+            let current = router
+                .read()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .clone();
+            current.oneshot(req)
+        }))
+    };
+
+    // Rebuild config when hotplate.toml / settings.json change (keeps the bound port)
+    let _config_reload = match reloader {
+        Some(reloader) => Some(spawn_config_reload(
+            reloader,
+            config.clone(),
+            state,
+            router,
+            file_watch,
+        )?),
+        None => {
+            // No reloader: just keep the file watcher alive for the server's lifetime
+            let _keep = file_watch;
+            None
+        }
+    };
 
-            print_banner(&config);
+    // Reload the cert/key in place when the files change (rotation, dev cert regeneration)
+    let _tls_reload = match (&tls_config, &config.cert, &config.key) {
+        (Some(tls), Some(cert), Some(key)) => Some(spawn_tls_reload(
+            tls.clone(),
+            cert.clone(),
+            key.clone(),
+            config.client_ca.clone(),
+            config.key_passphrase.clone(),
+            event_logger.clone(),
+        )?),
+        _ => None,
+    };
+
+    print_banner(&config);
+
+    match tls_config {
+        Some(tls_config) => {
             println!(
                 "  🚀 Listening on https://{}:{} ...",
                 config.host, config.port
             );
-            axum_server::bind_rustls(addr, tls_config)
-                .serve(app.into_make_service())
-                .await?;
+            let https = axum_server::from_tcp_rustls(listener, tls_config)?
+                .map(ClientCertAcceptor)
+                .serve(app.clone().into_make_service_with_connect_info::<SocketAddr>());
+            match http_listener {
+                Some(http_listener) => {
+                    let http = serve_plain(http_listener, app.layer(axum::Extension(PlainHttp)), config.h2c);
+                    tokio::try_join!(async { https.await.map_err(anyhow::Error::from) }, http)?;
+                }
+                None => https.await?,
+            }
         }
         _ => {
-            let original_port = config.port;
-            let mut listener_result = None;
+            println!(
+                "  🚀 Listening on http://{}:{} ...",
+                config.host, config.port
+            );
+            serve_plain(listener, app, config.h2c).await?;
+        }
+    }
 
-            for attempt in 0..=MAX_PORT_RETRIES {
-                let try_port = config.port + attempt;
-                if try_port < config.port {
-                    break; // overflow guard
-                }
-                let addr: SocketAddr = format!("{}:{}", config.host, try_port).parse()?;
+    Ok(())
+}
 
-                match tokio::net::TcpListener::bind(addr).await {
-                    Ok(l) => {
-                        config.port = try_port;
-                        listener_result = Some(l);
-                        break;
-                    }
-                    Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
-                        if attempt == 0 {
-                            eprintln!(
-                                "  ⚠ Port {} is in use, searching for an available port...",
-                                try_port
-                            );
-                        }
-                        continue;
-                    }
-                    Err(e) => return Err(e.into()),
+/// Subject of the client cert verified during the TLS handshake (mTLS).
+#[derive(Clone)]
+struct ClientCert(Arc<str>);
+
+/// Client cert subject of the connection the request arrived on, if any.
+fn client_cert_subject(req: &Request<Body>) -> Option<&str> {
+    let ClientCert(subject) = req.extensions().get::<Option<ClientCert>>()?.as_ref()?;
+    Some(subject)
+}
+
+/// Wraps the rustls acceptor to attach the verified client cert subject to
+/// every request on the connection.
+#[derive(Clone)]
+struct ClientCertAcceptor(RustlsAcceptor);
+
+impl<I, S> Accept<I, S> for ClientCertAcceptor
+where
+    I: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+    S: Send + 'static,
+{
+    type Stream = tokio_rustls::server::TlsStream<I>;
+    type Service = axum::middleware::AddExtension<S, Option<ClientCert>>;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = std::io::Result<(Self::Stream, Self::Service)>> + Send>,
+    >;
+
+    fn accept(&self, stream: I, service: S) -> Self::Future {
+        let acceptor = self.0.clone();
+        Box::pin(async move {
+            let (stream, service) = acceptor.accept(stream, service).await?;
+            let subject = stream
+                .get_ref()
+                .1
+                .peer_certificates()
+                .and_then(|chain| chain.first())
+                .and_then(|der| crate::cert::cert_subject(der))
+                .map(|s| ClientCert(s.into()));
+            Ok((stream, axum::Extension(subject).layer(service)))
+        })
+    }
+}
+
+/// Serve plain HTTP/1.1 — plus HTTP/2 with prior knowledge (h2c) when enabled.
+/// (Over TLS, HTTP/2 is negotiated via ALPN; see `cert::load_rustls_config`.)
+async fn serve_plain(listener: std::net::TcpListener, app: Router, h2c: bool) -> Result<()> {
+    if h2c {
+        // hyper's auto builder sniffs the h2 preface; HTTP/1.1 upgrades (WebSocket) keep working
+        axum_server::from_tcp(listener)?
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+            .await?;
+    } else {
+        axum::serve(
+            tokio::net::TcpListener::from_std(listener)?,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Bind `host:port`, trying the next ports (up to `MAX_PORT_RETRIES`) while they
+/// are in use. Returns the listener and the port actually bound.
+fn bind_with_retry(host: &str, port: u16) -> Result<(std::net::TcpListener, u16)> {
+    let original_port = port;
+
+    for attempt in 0..=MAX_PORT_RETRIES {
+        let Some(try_port) = original_port.checked_add(attempt) else {
+            break; // overflow guard
+        };
+        let addr: SocketAddr = format!("{}:{}", host, try_port).parse()?;
+
+        match std::net::TcpListener::bind(addr) {
+            Ok(listener) => {
+                listener.set_nonblocking(true)?;
+                return Ok((listener, try_port));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+                if attempt == 0 {
+                    eprintln!(
+                        "  ⚠ Port {} is in use, searching for an available port...",
+                        try_port
+                    );
                 }
+                continue;
             }
+            Err(e) => return Err(e.into()),
+        }
+    }
 
-            let listener = listener_result.ok_or_else(|| {
-                anyhow::anyhow!(
-                    "Ports {}-{} are all in use. Please free a port or choose a different one.",
-                    original_port,
-                    original_port + MAX_PORT_RETRIES
-                )
-            })?;
-
-            if config.port != original_port {
-                println!(
-                    "  ℹ Port {} was in use, switched to port {}.",
-                    original_port, config.port
-                );
-            }
+    anyhow::bail!(
+        "Ports {}-{} are all in use. Please free a port or choose a different one.",
+        original_port,
+        original_port.saturating_add(MAX_PORT_RETRIES)
+    )
+}
 
-            print_banner(&config);
-            println!(
-                "  🚀 Listening on http://{}:{} ...",
-                config.host, config.port
-            );
-            axum::serve(listener, app).await?;
+/// Start the root file watcher if live reload is on.
+fn spawn_file_watcher(
+    config: &Config,
+    reload_tx: &broadcast::Sender<String>,
+    event_logger: &EventLogger,
+) -> Result<Option<watcher::WatchHandle>> {
+    if !config.live_reload {
+        return Ok(None);
+    }
+    watcher::spawn(
+        config.root.clone(),
+        reload_tx.clone(),
+        &config.ignore_patterns,
+        &config.watch_extensions,
+        event_logger.clone(),
+    )
+    .map(Some)
+}
+
+// ───────────────────── Config reload ─────────────────────
+
+/// Re-resolves the config from its sources (CLI args captured at startup,
+/// env, hotplate.toml, settings.json).
+pub type ConfigReloader = Arc<dyn Fn() -> Result<Config> + Send + Sync>;
+
+/// Config files whose changes trigger a reload.
+fn config_sources(config: &Config) -> Vec<std::path::PathBuf> {
+    let mut files: Vec<_> = crate::project::PROJECT_CONFIG_FILES
+        .iter()
+        .map(|name| config.workspace.join(name))
+        .collect();
+    files.push(config.workspace.join(".vscode").join("settings.json"));
+    if let Some(ref f) = config.config_file {
+        if !files.contains(f) {
+            files.push(f.clone());
         }
     }
+    files
+}
 
-    Ok(())
+/// Stops a reload task (and its watchers) when dropped.
+struct ReloadHandle {
+    _watch: watcher::WatchHandle,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for ReloadHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Watch the config sources and, on change, rebuild the config and swap the
+/// router + state in place. Host, port and TLS stay as bound at startup.
+fn spawn_config_reload(
+    reloader: ConfigReloader,
+    mut config: Config,
+    mut state: Arc<AppState>,
+    router: Arc<std::sync::RwLock<Router>>,
+    mut file_watch: Option<watcher::WatchHandle>,
+) -> Result<ReloadHandle> {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let watch = watcher::spawn_config_watcher(&config_sources(&config), tx)?;
+
+    let task = tokio::spawn(async move {
+        while let Some(path) = rx.recv().await {
+            let rel = path
+                .strip_prefix(&config.workspace)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+
+            // Resolving reads config files (and may canonicalize paths) — keep it off the runtime
+            let reloader = reloader.clone();
+            let mut new_config = match tokio::task::spawn_blocking(move || reloader()).await {
+                Ok(Ok(c)) => c,
+                Ok(Err(e)) => {
+                    eprintln!("  ⚠ Config reload failed ({}): {:#} — keeping current config", rel, e);
+                    continue;
+                }
+                Err(e) => {
+                    eprintln!("  ⚠ Config reload failed ({}): {:#} — keeping current config", rel, e);
+                    continue;
+                }
+            };
+
+            // The listener is already bound — these need a restart
+            if new_config.host != config.host
+                || new_config.port != config.port
+                || new_config.http_port != config.http_port
+            {
+                eprintln!("  ⚠ Host/port changes need a restart (still on {}:{})", config.host, config.port);
+            }
+            // (The files themselves are reloaded in place — see spawn_tls_reload)
+            if new_config.cert != config.cert
+                || new_config.key != config.key
+                || new_config.client_ca != config.client_ca
+            {
+                eprintln!("  ⚠ TLS cert/key/client CA path changes need a restart");
+            }
+            new_config.host = config.host.clone();
+            new_config.port = config.port;
+            new_config.http_port = config.http_port;
+            if new_config.h2c != config.h2c {
+                eprintln!("  ⚠ h2c changes need a restart");
+            }
+            new_config.h2c = config.h2c;
+            new_config.cert = config.cert.clone();
+            new_config.key = config.key.clone();
+            new_config.client_ca = config.client_ca.clone();
+
+            // Restart the file watcher if what it watches changed
+            if new_config.root != config.root
+                || new_config.live_reload != config.live_reload
+                || new_config.ignore_patterns != config.ignore_patterns
+                || new_config.watch_extensions != config.watch_extensions
+            {
+                file_watch = None; // stop the old watcher first
+                match spawn_file_watcher(&new_config, &state.reload_tx, &state.event_logger) {
+                    Ok(w) => file_watch = w,
+                    Err(e) => eprintln!("  ⚠ Failed to restart file watcher: {}", e),
+                }
+            }
+
+            let mut new_state = (*state).clone();
+            new_state.live_reload = new_config.live_reload;
+            new_state.full_reload = new_config.full_reload;
+            new_state.capture = proxy_capture(&new_config);
+            state = Arc::new(new_state);
+
+            let new_router = build_router(state.clone(), &new_config);
+            *router.write().unwrap_or_else(std::sync::PoisonError::into_inner) = new_router;
+
+            config = new_config;
+            println!("  ⚙  Config reloaded ({})", rel);
+            state.event_logger.log(EventData::ConfigReload { path: rel.clone() });
+
+            // Browsers reload and reconnect to /__lr against the new router
+            let _ = state.reload_tx.send(rel);
+        }
+        drop(file_watch);
+    });
+
+    Ok(ReloadHandle { _watch: watch, task })
+}
+
+// ───────────────────── TLS reload ─────────────────────
+
+/// Watch the cert and key files and swap them into the running `RustlsConfig`.
+/// If the new files fail to load (e.g. half-written, mismatched, wrong
+/// passphrase) the current cert keeps being served.
+fn spawn_tls_reload(
+    tls: RustlsConfig,
+    cert: std::path::PathBuf,
+    key: std::path::PathBuf,
+    client_ca: Option<std::path::PathBuf>,
+    passphrase: Option<String>,
+    event_logger: EventLogger,
+) -> Result<ReloadHandle> {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let files: Vec<_> = [Some(cert.clone()), Some(key.clone()), client_ca.clone()]
+        .into_iter()
+        .flatten()
+        .collect();
+    let watch = watcher::spawn_config_watcher(&files, tx)?;
+
+    let task = tokio::spawn(async move {
+        while rx.recv().await.is_some() {
+            let result = crate::cert::server_config(&cert, &key, client_ca.as_deref(), passphrase.as_deref())
+                .map(|server_config| tls.reload_from_config(server_config));
+            let error = match result {
+                Ok(()) => {
+                    println!("  🔒 TLS cert reloaded ({})", cert.display());
+                    None
+                }
+                Err(e) => {
+                    eprintln!("  ⚠ TLS reload failed: {:#} — keeping current cert", e);
+                    Some(format!("{:#}", e))
+                }
+            };
+            event_logger.log(EventData::TlsReload {
+                cert: cert.display().to_string(),
+                key: key.display().to_string(),
+                ok: error.is_none(),
+                error,
+            });
+        }
+    });
+
+    Ok(ReloadHandle { _watch: watch, task })
 }
@@ -4,7 +4,8 @@ use anyhow::Result;
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::sync::broadcast;
 
@@ -84,6 +85,11 @@ fn should_ignore(
     })
 }
 
+/// Keeps a watcher alive. Dropping it stops the watcher and ends its thread.
+pub struct WatchHandle {
+    _watcher: Arc<Mutex<RecommendedWatcher>>,
+}
+
 fn is_relevant_event(kind: &EventKind) -> bool {
     matches!(
         kind,
@@ -96,13 +102,14 @@ fn is_relevant_event(kind: &EventKind) -> bool {
 /// `ignore_patterns` are user-provided glob patterns to skip (e.g. "**/*.scss").
 /// `watch_extensions` limits which file extensions trigger reloads (e.g. ["html", "css", "js"]).
 /// If empty, the default UI-related extensions are used. Pass `["*"]` to watch all files.
+/// Watching stops when the returned handle is dropped.
 pub fn spawn(
     root: PathBuf,
     reload_tx: broadcast::Sender<String>,
     ignore_patterns: &[String],
     watch_extensions: &[String],
     event_logger: EventLogger,
-) -> Result<()> {
+) -> Result<WatchHandle> {
     let (tx, rx) = std::sync::mpsc::channel::<Result<Event, notify::Error>>();
 
     let mut watcher = RecommendedWatcher::new(
@@ -138,7 +145,7 @@ pub fn spawn(
     std::thread::Builder::new()
         .name("fs-watcher".into())
         .spawn(move || {
-            let _watcher = watcher; // prevent drop
+            // Loop ends once the WatchHandle (and with it the event sender) is dropped
             let mut last_reload = Instant::now();
             let debounce = Duration::from_millis(150);
 
@@ -189,5 +196,92 @@ pub fn spawn(
             }
         })?;
 
-    Ok(())
+    Ok(WatchHandle { _watcher: Arc::new(Mutex::new(watcher)) })
+}
+
+/// Watch individual files (e.g. `hotplate.toml`, `.vscode/settings.json`, TLS cert/key).
+///
+/// Their parent directories are watched non-recursively, so files created after
+/// startup are picked up too. A parent that doesn't exist yet (no `.vscode/`)
+/// is waited for from its nearest existing ancestor. Once a file changes and
+/// writes have settled (300ms), its path is sent to `tx`.
+pub fn spawn_config_watcher(
+    files: &[PathBuf],
+    tx: tokio::sync::mpsc::UnboundedSender<PathBuf>,
+) -> Result<WatchHandle> {
+    let (event_tx, rx) = std::sync::mpsc::channel::<Result<Event, notify::Error>>();
+
+    let mut watcher = RecommendedWatcher::new(
+        move |res| {
+            let _ = event_tx.send(res);
+        },
+        notify::Config::default(),
+    )?;
+
+    // Canonicalize via the nearest existing ancestor so paths match what notify reports
+    let files: Vec<PathBuf> = files
+        .iter()
+        .filter_map(|f| Some(canonical_parent(f.parent()?)?.join(f.file_name()?)))
+        .collect();
+    let mut dirs = HashSet::new();
+    for file in &files {
+        let Some(parent) = file.parent() else { continue };
+        let dir = parent.ancestors().find(|d| d.is_dir()).unwrap_or(parent).to_path_buf();
+        if dirs.insert(dir.clone()) {
+            watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+        }
+    }
+    let watcher = Arc::new(Mutex::new(watcher));
+    // The thread only borrows the watcher: dropping the handle still ends it
+    let weak = Arc::downgrade(&watcher);
+
+    std::thread::Builder::new()
+        .name("config-watcher".into())
+        .spawn(move || {
+            let settle = Duration::from_millis(300);
+
+            while let Ok(event) = rx.recv() {
+                let Ok(event) = event else { continue };
+                if !is_relevant_event(&event.kind) {
+                    continue;
+                }
+                // A missing parent appeared: watch it (and whatever of the rest
+                // already exists), and report a file that came with it
+                let created = matches!(event.kind, EventKind::Create(_))
+                    .then(|| event.paths.iter().find(|p| p.is_dir() && files.iter().any(|f| f.starts_with(p))))
+                    .flatten();
+                let path = match created {
+                    Some(dir) => {
+                        let Some(watcher) = weak.upgrade() else { break };
+                        let mut watcher = watcher.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+                        for file in files.iter().filter(|f| f.starts_with(dir)) {
+                            let Some(parent) = file.parent() else { continue };
+                            for d in parent.ancestors().take_while(|d| d.starts_with(dir)).filter(|d| d.is_dir()) {
+                                if dirs.insert(d.to_path_buf()) {
+                                    let _ = watcher.watch(d, RecursiveMode::NonRecursive);
+                                }
+                            }
+                        }
+                        files.iter().find(|f| f.starts_with(dir) && f.is_file()).cloned()
+                    }
+                    None => event.paths.iter().find(|p| files.contains(p)).cloned(),
+                };
+                let Some(path) = path else { continue };
+                // Editors often write in several steps — wait, then drop the burst
+                std::thread::sleep(settle);
+                while rx.try_recv().is_ok() {}
+                if tx.send(path).is_err() {
+                    break;
+                }
+            }
+        })?;
+
+    Ok(WatchHandle { _watcher: watcher })
+}
+
+/// `dir` canonicalized through its nearest existing ancestor (the rest is appended as is).
+fn canonical_parent(dir: &Path) -> Option<PathBuf> {
+    let existing = dir.ancestors().find(|d| d.is_dir())?;
+    let rest = dir.strip_prefix(existing).ok()?;
+    Some(std::fs::canonicalize(existing).ok()?.join(rest))
 }